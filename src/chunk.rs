@@ -0,0 +1,334 @@
+use crate::reader::{DataReader, Reader};
+use crate::table::{read_header, read_record, write_gamma, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChunkType {
+    Riff,
+    Array,
+    SparseArray,
+    Table,
+    SparseTable,
+}
+
+impl ChunkType {
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte & 0x0F {
+            0 => Some(ChunkType::Riff),
+            1 => Some(ChunkType::Array),
+            2 => Some(ChunkType::SparseArray),
+            3 => Some(ChunkType::Table),
+            4 => Some(ChunkType::SparseTable),
+            _ => None,
+        }
+    }
+
+    pub fn is_list(&self) -> bool {
+        *self != ChunkType::Riff
+    }
+
+    pub fn is_table(&self) -> bool {
+        matches!(self, ChunkType::Table | ChunkType::SparseTable)
+    }
+
+    pub fn is_sparse(&self) -> bool {
+        matches!(self, ChunkType::SparseArray | ChunkType::SparseTable)
+    }
+}
+
+/// One entry of an array-like chunk
+#[derive(Debug)]
+pub struct Element {
+    pub index: u32,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub struct Chunk {
+    pub id: String,
+    /// position of the chunk id in the decompressed savegame body
+    pub offset: usize,
+    /// length of the whole chunk including its framing
+    pub len: usize,
+    pub chunk_type: ChunkType,
+    /// raw table header, only present for table chunks
+    pub header: Option<Vec<u8>>,
+    /// elements of array-like chunks
+    pub elements: Vec<Element>,
+    /// payload of RIFF chunks
+    pub data: Vec<u8>,
+}
+
+impl Chunk {
+    /// Decode every element of a table chunk, other chunks have no records
+    pub fn records(&self) -> Vec<(u32, Value)> {
+        self.try_records().unwrap_or_else(|error| panic!("Corrupt chunk {}: {}", self.id, error))
+    }
+
+    /// `records`, but a header or element that does not decode is an error
+    /// instead of a panic
+    pub fn try_records(&self) -> Result<Vec<(u32, Value)>, String> {
+        let header = match &self.header {
+            Some(header) => read_header(header)?,
+            None => return Ok(Vec::new()),
+        };
+        self.elements
+            .iter()
+            .map(|e| {
+                let record = read_record(&header, &e.data).map_err(|error| format!("element {}: {}", e.index, error))?;
+                Ok((e.index, record))
+            })
+            .collect()
+    }
+
+    /// size of the chunk contents, without framing
+    pub fn size(&self) -> usize {
+        self.data.len() + self.elements.iter().map(|e| e.data.len()).sum::<usize>()
+    }
+}
+
+/// Look up a chunk by its four letter id
+pub fn find<'a>(chunks: &'a [Chunk], id: &str) -> Option<&'a Chunk> {
+    chunks.iter().find(|c| c.id == id)
+}
+
+/// Make sure `len` more bytes can be read
+pub(crate) fn need(reader: &impl Reader, len: usize) -> Result<(), String> {
+    if reader.read_leftover().len() < len {
+        return Err(format!("unexpected end of data at offset {}", reader.position()));
+    }
+    Ok(())
+}
+
+pub(crate) fn read_u8(reader: &mut impl Reader) -> Result<u8, String> {
+    need(reader, 1)?;
+    Ok(reader.read_u8())
+}
+
+pub(crate) fn read_bytes(reader: &mut impl Reader, len: usize) -> Result<Vec<u8>, String> {
+    need(reader, len)?;
+    Ok(reader.read(len).to_vec())
+}
+
+fn skip(reader: &mut impl Reader, len: usize) -> Result<(), String> {
+    need(reader, len)?;
+    reader.read(len);
+    Ok(())
+}
+
+pub(crate) fn read_gamma(reader: &mut impl Reader) -> Result<u32, String> {
+    need(reader, 1)?;
+    let position = reader.position();
+    let len = match reader.load(position, position + 1)[0].leading_ones() {
+        prefix @ 0..=3 => prefix as usize + 1,
+        4 => 5,
+        _ => return Err(format!("invalid gamma at offset {}", position)),
+    };
+    need(reader, len)?;
+    Ok(reader.read_gamma())
+}
+
+/// Read one RIFF payload or a list of elements, the reader should be
+/// positioned right after the chunk type byte, without `contents` only the
+/// framing is followed and nothing is copied
+fn read_chunk_body(
+    reader: &mut impl Reader,
+    id: String,
+    type_byte: u8,
+    contents: bool,
+) -> Result<Chunk, String> {
+    let offset = reader.position() - 5;
+    let chunk_type = ChunkType::from_byte(type_byte)
+        .ok_or_else(|| format!("chunk {} at offset {} has unknown type {}", id, offset, type_byte))?;
+    let mut chunk = Chunk {
+        id,
+        offset,
+        len: 0,
+        chunk_type,
+        header: None,
+        elements: Vec::new(),
+        data: Vec::new(),
+    };
+    if chunk_type == ChunkType::Riff {
+        let high = read_u8(reader)? as usize;
+        let low = read_u8(reader)? as usize;
+        let len = ((type_byte as usize >> 4) << 24) | high << 16 | low << 8 | read_u8(reader)? as usize;
+        if contents {
+            chunk.data = read_bytes(reader, len)?;
+        } else {
+            skip(reader, len)?;
+        }
+        chunk.len = reader.position() - chunk.offset;
+        return Ok(chunk);
+    }
+
+    let mut index = 0;
+    if chunk_type.is_table() {
+        let len = (read_gamma(reader)? as usize).saturating_sub(1);
+        if contents {
+            chunk.header = Some(read_bytes(reader, len)?);
+        } else {
+            skip(reader, len)?;
+        }
+    }
+    loop {
+        let len = read_gamma(reader)? as usize;
+        if len == 0 {
+            break;
+        }
+        let end = reader.position() + len - 1;
+        if chunk_type.is_sparse() {
+            index = read_gamma(reader)?;
+        }
+        if end < reader.position() {
+            return Err(format!("element {} of chunk {} is shorter than its index", index, chunk.id));
+        }
+        if !contents {
+            skip(reader, end - reader.position())?;
+            continue;
+        }
+        let data = read_bytes(reader, end - reader.position())?;
+        if !data.is_empty() || chunk_type.is_sparse() {
+            chunk.elements.push(Element { index, data });
+        }
+        if !chunk_type.is_sparse() {
+            index += 1;
+        }
+    }
+    chunk.len = reader.position() - chunk.offset;
+    Ok(chunk)
+}
+
+/// Result of reading the chunks of a possibly damaged savegame
+pub struct ChunkScan {
+    pub chunks: Vec<Chunk>,
+    /// first framing error, the chunks before it are intact
+    pub error: Option<String>,
+    /// bytes after the end marker
+    pub trailing: usize,
+}
+
+fn scan(data: &[u8], contents: bool) -> ChunkScan {
+    let mut reader = DataReader::new(data);
+    let mut scan = ChunkScan {
+        chunks: Vec::new(),
+        error: None,
+        trailing: 0,
+    };
+    loop {
+        let Ok(id) = read_bytes(&mut reader, 4) else {
+            scan.error = Some("end marker is missing".to_string());
+            return scan;
+        };
+        if id == [0, 0, 0, 0] {
+            break;
+        }
+        let id = String::from_utf8_lossy(&id).to_string();
+        let chunk = read_u8(&mut reader).and_then(|type_byte| read_chunk_body(&mut reader, id, type_byte, contents));
+        match chunk {
+            Ok(chunk) => scan.chunks.push(chunk),
+            Err(error) => {
+                scan.error = Some(error);
+                return scan;
+            }
+        }
+    }
+    scan.trailing = reader.read_leftover().len();
+    scan
+}
+
+/// Read chunks until the end marker or the first framing error
+pub fn read_chunks_lenient(data: &[u8]) -> ChunkScan {
+    scan(data, true)
+}
+
+/// Split the decompressed savegame body into its chunks
+pub fn read_chunks(data: &[u8]) -> Vec<Chunk> {
+    let scan = read_chunks_lenient(data);
+    if let Some(error) = scan.error {
+        panic!("Corrupt savegame: {}", error);
+    }
+    scan.chunks
+}
+
+/// Where a chunk sits in the decompressed savegame body
+#[derive(Debug)]
+pub struct ChunkEntry {
+    pub id: String,
+    /// position of the chunk id
+    pub offset: usize,
+    /// length of the whole chunk including its framing
+    pub len: usize,
+    pub kind: ChunkType,
+}
+
+/// Offsets and lengths of every chunk, built by following the framing only
+/// so tools can seek to the raw chunk bytes themselves. Corrupt framing is
+/// an error.
+pub fn chunk_index(data: &[u8]) -> Result<Vec<ChunkEntry>, String> {
+    let scan = scan(data, false);
+    if let Some(error) = scan.error {
+        return Err(error);
+    }
+    Ok(scan
+        .chunks
+        .into_iter()
+        .map(|chunk| ChunkEntry {
+            id: chunk.id,
+            offset: chunk.offset,
+            len: chunk.len,
+            kind: chunk.chunk_type,
+        })
+        .collect())
+}
+
+fn write_chunk(out: &mut Vec<u8>, chunk: &Chunk) {
+    out.extend_from_slice(chunk.id.as_bytes());
+    let type_byte = match chunk.chunk_type {
+        ChunkType::Riff => {
+            let len = chunk.data.len() as u32;
+            out.push(((len >> 24) << 4) as u8);
+            out.extend_from_slice(&len.to_be_bytes()[1..]);
+            out.extend_from_slice(&chunk.data);
+            return;
+        }
+        ChunkType::Array => 1,
+        ChunkType::SparseArray => 2,
+        ChunkType::Table => 3,
+        ChunkType::SparseTable => 4,
+    };
+    out.push(type_byte);
+    if let Some(header) = &chunk.header {
+        write_gamma(out, header.len() as u32 + 1);
+        out.extend_from_slice(header);
+    }
+    let mut index = 0;
+    for element in chunk.elements.iter() {
+        if chunk.chunk_type.is_sparse() {
+            let mut data = Vec::new();
+            write_gamma(&mut data, element.index);
+            data.extend_from_slice(&element.data);
+            write_gamma(out, data.len() as u32 + 1);
+            out.extend_from_slice(&data);
+            continue;
+        }
+        // arrays have no index on disk, gaps are written as empty elements
+        while index < element.index {
+            write_gamma(out, 1);
+            index += 1;
+        }
+        write_gamma(out, element.data.len() as u32 + 1);
+        out.extend_from_slice(&element.data);
+        index += 1;
+    }
+    write_gamma(out, 0);
+}
+
+/// Build a savegame body from chunks, the reverse of `read_chunks`
+pub fn write_chunks(chunks: &[Chunk]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for chunk in chunks {
+        write_chunk(&mut out, chunk);
+    }
+    out.extend_from_slice(&[0, 0, 0, 0]);
+    out
+}
@@ -0,0 +1,97 @@
+use crate::chunk::{find, read_chunks_lenient};
+use crate::header;
+use crate::reader::Savegame;
+use crate::registry;
+use crate::summary::newgrfs;
+use crate::table::Value;
+
+const GAMELOG_ACTIONS: [&str; 7] = ["start", "load", "grf", "cheat", "setting", "grfbug", "emergency"];
+
+/// Drop empty lists, gamelog entries carry a list per change type of which
+/// only one is filled
+fn prune(value: &Value) -> Value {
+    match value {
+        Value::Struct(fields) => Value::Struct(
+            fields
+                .iter()
+                .filter(|(_, v)| !matches!(v, Value::List(values) if values.is_empty()))
+                .map(|(k, v)| (k.clone(), prune(v)))
+                .collect(),
+        ),
+        Value::List(values) => Value::List(values.iter().map(prune).collect()),
+        value => value.clone(),
+    }
+}
+
+/// Print what developers ask for in a crash report: how far the savegame
+/// could be read, the gamelog and the NewGRFs in use
+pub fn print(path: String) {
+    println!("Savegame: {}", path);
+    // a wrong tag in front of an intact body can be fixed
+    let hint = || {
+        if let Some(compression) = header::detect_compression(&path) {
+            println!("The body looks like {:?}, try fix-compression", compression);
+        }
+    };
+    let (savegame, error) = match Savegame::open_lenient(path.clone()) {
+        Ok(opened) => opened,
+        Err(error) => {
+            println!("{}", error);
+            hint();
+            return;
+        }
+    };
+    println!("Version: {}, compression: {:?}", savegame.version, savegame.compression);
+    match error {
+        Some(error) => {
+            println!("Decompression failed after {} bytes: {}", savegame.data.len(), error);
+            if savegame.data.is_empty() {
+                hint();
+            }
+        }
+        None => println!("Decompressed {} bytes", savegame.data.len()),
+    }
+
+    let scan = read_chunks_lenient(&savegame.data);
+    println!("\nChunks: {}", scan.chunks.len());
+    if let Some(last) = scan.chunks.last() {
+        println!("Last valid chunk: {} at offset {}, {} bytes", last.id, last.offset, last.len);
+    }
+    if let Some(error) = &scan.error {
+        println!("Framing error: {}", error);
+    }
+    if scan.trailing > 0 {
+        println!("Trailing data after the end marker: {} bytes", scan.trailing);
+    }
+    let mut check = registry::check(savegame.version, &scan.chunks);
+    // a truncated savegame misses everything after the damage anyway
+    if scan.error.is_some() {
+        check.missing.clear();
+    }
+    registry::print_check(savegame.version, &check);
+
+    println!("\nNewGRFs:");
+    for grf in newgrfs(&scan.chunks) {
+        println!("  {} {} {} (version {})", grf.grfid_hex(), grf.md5sum, grf.filename, grf.version);
+    }
+
+    println!("\nGamelog:");
+    match find(&scan.chunks, "GLOG") {
+        Some(chunk) if chunk.header.is_some() => {
+            for (_, record) in chunk.records() {
+                let action = record.get("at").and_then(Value::as_i64).unwrap_or(-1);
+                let name = GAMELOG_ACTIONS.get(action as usize).unwrap_or(&"unknown");
+                let tick = record.get("tick").and_then(Value::as_i64).unwrap_or(0);
+                println!("  tick {}: {}", tick, name);
+                if let Some(Value::List(changes)) = record.get("action").map(prune) {
+                    for change in changes {
+                        println!("    {}", change);
+                    }
+                }
+            }
+        }
+        // savegames from before table chunks carry no field names
+        Some(chunk) => println!("  {} entries in the old format, {} bytes", chunk.elements.len(), chunk.size()),
+        None => println!("  not found"),
+    }
+}
@@ -0,0 +1,294 @@
+use crate::chunk::{write_chunks, Chunk, ChunkType, Element};
+use crate::reader::{CompressionType, Savegame};
+use crate::registry::{known_chunk, KNOWN_CHUNKS};
+use crate::table::{write_header, write_record, Field, Value};
+use crate::table::{FILE_HAS_LENGTH_FIELD, FILE_I32, FILE_I64, FILE_STRING, FILE_U16, FILE_U32, FILE_U64, FILE_U8};
+
+/// Chunks `standard` knows how to generate
+pub const STANDARD_CHUNKS: [&str; 9] = ["DATE", "MAPS", "MAPT", "MAPH", "PLYR", "VEHS", "CITY", "NGRF", "GLOG"];
+/// Map layers `standard` fills with zeros, MAP2 has two bytes per tile
+const MAP_LAYERS: [(&str, u32); 6] = [("MAPO", 1), ("MAP2", 2), ("M3LO", 1), ("M3HI", 1), ("MAP5", 1), ("MAPE", 1)];
+
+const MAP_SIZE: u32 = 64;
+/// 1950-01-01
+const START_DATE: i64 = 712223;
+
+/// Builds small savegames from scratch, so tests and tools do not need
+/// real savegames of several megabytes
+pub struct Fixture {
+    version: u16,
+    compression: CompressionType,
+    chunks: Vec<Chunk>,
+}
+
+/// A struct value from (key, value) pairs
+pub fn record(fields: &[(&str, Value)]) -> Value {
+    Value::Struct(fields.iter().map(|(k, v)| (k.to_string(), v.clone())).collect())
+}
+
+fn string(key: &str) -> Field {
+    Field::new(key, FILE_STRING | FILE_HAS_LENGTH_FIELD)
+}
+
+fn text(value: &str) -> Value {
+    Value::Str(value.to_string())
+}
+
+impl Fixture {
+    pub fn new(version: u16, compression: CompressionType) -> Self {
+        Fixture {
+            version,
+            compression,
+            chunks: Vec::new(),
+        }
+    }
+
+    /// Add a table chunk, records are (index, value) pairs
+    pub fn table(mut self, id: &str, sparse: bool, fields: Vec<Field>, records: Vec<(u32, Value)>) -> Self {
+        let elements = records
+            .iter()
+            .map(|(index, value)| Element {
+                index: *index,
+                data: write_record(&fields, value),
+            })
+            .collect();
+        self.chunks.push(Chunk {
+            id: id.to_string(),
+            offset: 0,
+            len: 0,
+            chunk_type: if sparse { ChunkType::SparseTable } else { ChunkType::Table },
+            header: Some(write_header(&fields)),
+            elements,
+            data: Vec::new(),
+        });
+        self
+    }
+
+    pub fn riff(mut self, id: &str, data: Vec<u8>) -> Self {
+        self.chunks.push(Chunk {
+            id: id.to_string(),
+            offset: 0,
+            len: 0,
+            chunk_type: ChunkType::Riff,
+            header: None,
+            elements: Vec::new(),
+            data,
+        });
+        self
+    }
+
+    /// The savegame, `path` is only remembered, nothing is written yet
+    pub fn build(self, path: &str) -> Savegame {
+        Savegame {
+            path: path.to_string(),
+            data: write_chunks(&self.chunks),
+            version: self.version,
+            minor_version: 0,
+            compression: self.compression,
+        }
+    }
+}
+
+/// The `STANDARD_CHUNKS` savegames of `version` have, followed by every
+/// other chunk the registry requires at `version`, which `standard` writes
+/// as empty stubs so the fixture passes `registry::check`
+pub fn default_chunks(version: u16) -> Vec<&'static str> {
+    let written = |id: &&str| known_chunk(id).is_some_and(|known| known.written_by(version));
+    let stubs = KNOWN_CHUNKS
+        .iter()
+        .filter(|known| known.required && known.written_by(version) && !STANDARD_CHUNKS.contains(&known.id))
+        .map(|known| known.id);
+    STANDARD_CHUNKS.iter().copied().filter(written).chain(stubs).collect()
+}
+
+/// A fixture with the given chunks out of `STANDARD_CHUNKS`: a 64x64 map
+/// in 1950 with two companies, a few vehicles, two towns and OpenGFX, started
+/// as a sub-arctic game by OpenTTD 14.1. Other chunks of the registry are
+/// empty: map layers are all zero and anything else is a table without
+/// records.
+pub fn standard(version: u16, compression: CompressionType, chunks: &[&str]) -> Fixture {
+    let mut fixture = Fixture::new(version, compression);
+    for id in chunks {
+        fixture = match *id {
+            "DATE" => fixture.table(
+                "DATE",
+                false,
+                vec![Field::new("date", FILE_I32), Field::new("date_fract", FILE_U16)],
+                vec![(0, record(&[("date", Value::Int(START_DATE)), ("date_fract", Value::UInt(0))]))],
+            ),
+            "MAPS" => fixture.table(
+                "MAPS",
+                false,
+                vec![Field::new("dim_x", FILE_U32), Field::new("dim_y", FILE_U32)],
+                vec![(0, record(&[("dim_x", Value::UInt(MAP_SIZE as u64)), ("dim_y", Value::UInt(MAP_SIZE as u64))]))],
+            ),
+            // clear land with a lake in the middle
+            "MAPT" => fixture.riff(
+                "MAPT",
+                (0..MAP_SIZE * MAP_SIZE)
+                    .map(|i| {
+                        let (x, y) = (i % MAP_SIZE, i / MAP_SIZE);
+                        if x.abs_diff(MAP_SIZE / 2) < 8 && y.abs_diff(MAP_SIZE / 2) < 8 { 6 << 4 } else { 0 }
+                    })
+                    .collect(),
+            ),
+            "MAPH" => fixture.riff("MAPH", (0..MAP_SIZE * MAP_SIZE).map(|i| (i % MAP_SIZE / 16) as u8).collect()),
+            "PLYR" => fixture.table(
+                "PLYR",
+                true,
+                vec![
+                    string("name"),
+                    string("president_name"),
+                    Field::new("money", FILE_I64),
+                    Field::new("current_loan", FILE_I64),
+                    Field::structs(
+                        "cur_economy",
+                        vec![Field::new("delivered_cargo", FILE_U32 | FILE_HAS_LENGTH_FIELD)],
+                    ),
+                ],
+                vec![
+                    (
+                        0,
+                        record(&[
+                            ("name", text("Fixture Transport")),
+                            ("president_name", text("A. Tester")),
+                            ("money", Value::Int(100000)),
+                            ("current_loan", Value::Int(300000)),
+                            (
+                                "cur_economy",
+                                Value::List(vec![record(&[(
+                                    "delivered_cargo",
+                                    Value::List(vec![Value::UInt(120), Value::UInt(30)]),
+                                )])]),
+                            ),
+                        ]),
+                    ),
+                    (
+                        1,
+                        record(&[
+                            ("name", text("Second Rail")),
+                            ("money", Value::Int(-2500)),
+                            ("current_loan", Value::Int(500000)),
+                        ]),
+                    ),
+                ],
+            ),
+            // a train engine and wagon, a road vehicle and an aircraft with its shadow
+            "VEHS" => fixture.table(
+                "VEHS",
+                true,
+                vec![
+                    Field::new("type", FILE_U8),
+                    Field::structs("common", vec![Field::new("owner", FILE_U8), Field::new("subtype", FILE_U8)]),
+                ],
+                [(0, 0, 1), (0, 0, 0), (1, 1, 1), (3, 0, 2), (3, 0, 4)]
+                    .iter()
+                    .enumerate()
+                    .map(|(index, (vehicle_type, owner, subtype))| {
+                        let common = record(&[("owner", Value::UInt(*owner)), ("subtype", Value::UInt(*subtype))]);
+                        (index as u32, record(&[("type", Value::UInt(*vehicle_type)), ("common", Value::List(vec![common]))]))
+                    })
+                    .collect(),
+            ),
+            "CITY" => fixture.table(
+                "CITY",
+                false,
+                vec![string("name"), Field::new("xy", FILE_U32)],
+                vec![
+                    (0, record(&[("name", text("Fixtureton")), ("xy", Value::UInt(10 * MAP_SIZE as u64 + 10))])),
+                    (1, record(&[("name", text("Mockford")), ("xy", Value::UInt(50 * MAP_SIZE as u64 + 50))])),
+                ],
+            ),
+            "NGRF" => fixture.table(
+                "NGRF",
+                false,
+                vec![
+                    Field::new("grfid", FILE_U32),
+                    string("filename"),
+                    Field::new("md5sum", FILE_U8 | FILE_HAS_LENGTH_FIELD),
+                    Field::new("version", FILE_U32),
+                ],
+                vec![(
+                    0,
+                    record(&[
+                        // "MG\x01\x01" as OpenTTD keeps it in memory
+                        ("grfid", Value::UInt(0x0101474D)),
+                        ("filename", text("opengfx/ogfx1_base.grf")),
+                        ("md5sum", Value::List((0..16).map(Value::UInt).collect())),
+                        ("version", Value::UInt(1)),
+                    ]),
+                )],
+            ),
+            // the start of the game: the climate and the OpenTTD revision
+            "GLOG" => fixture.table(
+                "GLOG",
+                false,
+                vec![
+                    Field::new("at", FILE_U8),
+                    Field::new("tick", FILE_U64),
+                    Field::structs(
+                        "action",
+                        vec![
+                            Field::new("ct", FILE_U8),
+                            Field::structs(
+                                "mode",
+                                vec![Field::new("mode.mode", FILE_U8), Field::new("mode.landscape", FILE_U8)],
+                            ),
+                            Field::structs(
+                                "revision",
+                                vec![
+                                    string("revision.text"),
+                                    Field::new("revision.newgrf", FILE_U32),
+                                    Field::new("revision.slver", FILE_U16),
+                                    Field::new("revision.modified", FILE_U8),
+                                ],
+                            ),
+                        ],
+                    ),
+                ],
+                vec![(
+                    0,
+                    record(&[
+                        ("at", Value::UInt(0)),
+                        ("tick", Value::UInt(0)),
+                        (
+                            "action",
+                            Value::List(vec![
+                                record(&[
+                                    ("ct", Value::UInt(0)),
+                                    (
+                                        "mode",
+                                        Value::List(vec![record(&[
+                                            ("mode.mode", Value::UInt(1)),
+                                            ("mode.landscape", Value::UInt(1)),
+                                        ])]),
+                                    ),
+                                    ("revision", Value::List(Vec::new())),
+                                ]),
+                                record(&[
+                                    ("ct", Value::UInt(1)),
+                                    ("mode", Value::List(Vec::new())),
+                                    (
+                                        "revision",
+                                        Value::List(vec![record(&[
+                                            ("revision.text", text("14.1")),
+                                            ("revision.newgrf", Value::UInt(0x14100000)),
+                                            ("revision.slver", Value::UInt(version as u64)),
+                                            ("revision.modified", Value::UInt(0)),
+                                        ])]),
+                                    ),
+                                ]),
+                            ]),
+                        ),
+                    ]),
+                )],
+            ),
+            _ => match MAP_LAYERS.iter().find(|(layer, _)| layer == id) {
+                Some((_, width)) => fixture.riff(id, vec![0; (MAP_SIZE * MAP_SIZE * width) as usize]),
+                None if known_chunk(id).is_some() => fixture.table(id, false, Vec::new(), Vec::new()),
+                None => panic!("No fixture for chunk {}, known are {} and the registry", id, STANDARD_CHUNKS.join(", ")),
+            },
+        };
+    }
+    fixture
+}
@@ -0,0 +1,88 @@
+use crate::chunk::{find, Chunk};
+use crate::summary::{companies, date_number, map_size, newgrfs};
+use crate::table::Value;
+use serde_json::json;
+
+/// Version of the game info the fields below follow, 7 added ticks_playing
+const GAME_INFO_VERSION: u32 = 7;
+/// MAX_COMPANIES, the real limit is a server setting not in the savegame
+const MAX_COMPANIES: u32 = 15;
+
+/// First day of `year`, in days since 1-1-0
+fn start_of_year(year: i64) -> i64 {
+    let leap_years = if year == 0 { 0 } else { (year - 1) / 4 - (year - 1) / 100 + (year - 1) / 400 + 1 };
+    365 * year + leap_years
+}
+
+/// A setting from the PATS chunk, stored under its full name
+fn setting(chunks: &[Chunk], name: &str) -> Option<i64> {
+    let (_, record) = find(chunks, "PATS")?.records().into_iter().next()?;
+    record.get(name).and_then(Value::as_i64)
+}
+
+/// Last value of `key` logged in the gamelog. The changes keep their old
+/// names, e.g. `revision.text` and `mode.landscape`.
+fn gamelog(chunks: &[Chunk], key: &str) -> Option<Value> {
+    find(chunks, "GLOG")?
+        .records()
+        .iter()
+        .rev()
+        .find_map(|(_, record)| record.find(key).cloned())
+}
+
+/// Revision of the OpenTTD that saved the game last, a string since
+/// SLV_STRING_GAMELOG and a zero padded byte array before
+fn revision(chunks: &[Chunk]) -> String {
+    match gamelog(chunks, "revision.text") {
+        Some(Value::Str(text)) => text,
+        Some(Value::List(bytes)) => {
+            let bytes: Vec<u8> = bytes
+                .iter()
+                .filter_map(Value::as_i64)
+                .map(|b| b as u8)
+                .take_while(|b| *b != 0)
+                .collect();
+            String::from_utf8_lossy(&bytes).to_string()
+        }
+        _ => String::new(),
+    }
+}
+
+/// The savegame as OpenTTD describes a running server to the game
+/// coordinator, so server list tools can read it. Anything only a running
+/// server knows, like clients and passwords, is left at zero or false.
+pub fn json(name: &str, chunks: &[Chunk]) -> serde_json::Value {
+    let game_date = date_number(chunks).unwrap_or(0);
+    let start_date = setting(chunks, "game_creation.starting_year").map_or(game_date, start_of_year);
+    let (map_width, map_height) = map_size(chunks).unwrap_or((0, 0));
+    let newgrfs: Vec<serde_json::Value> = newgrfs(chunks)
+        .iter()
+        .map(|grf| json!({"grfid": grf.grfid, "md5sum": grf.md5sum, "name": grf.filename}))
+        .collect();
+    let tick_counter = find(chunks, "DATE")
+        .and_then(|chunk| chunk.records().into_iter().next())
+        .and_then(|(_, record)| record.get("tick_counter").and_then(Value::as_i64))
+        .unwrap_or(0);
+    json!({
+        "game_info_version": GAME_INFO_VERSION,
+        "newgrfs": newgrfs,
+        "game_date": game_date,
+        "start_date": start_date,
+        "companies_max": MAX_COMPANIES,
+        "companies_on": companies(chunks).len(),
+        "spectators_max": 0,
+        "name": name,
+        "openttd_version": revision(chunks),
+        "use_password": false,
+        "is_dedicated": false,
+        "clients_max": 0,
+        "clients_on": 0,
+        "spectators_on": 0,
+        "map_width": map_width,
+        "map_height": map_height,
+        "map_type": gamelog(chunks, "mode.landscape").as_ref().and_then(Value::as_i64).unwrap_or(0),
+        "ticks_playing": tick_counter,
+        "gamescript_version": -1,
+        "gamescript_name": "",
+    })
+}
@@ -0,0 +1,75 @@
+use crate::reader::CompressionType;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+
+/// Size of the savegame header: compression tag, version and minor version
+pub const HEADER_SIZE: usize = 8;
+
+fn truncated(len: usize) -> String {
+    format!("Header truncated, the file has only {} bytes", len)
+}
+
+/// The first 8 bytes of the file, a shorter file is an error
+pub fn read_header(path: &str) -> Result<[u8; HEADER_SIZE], String> {
+    let mut start = Vec::new();
+    File::open(path).unwrap().take(HEADER_SIZE as u64).read_to_end(&mut start).unwrap();
+    start.try_into().map_err(|start: Vec<u8>| truncated(start.len()))
+}
+
+/// Build a header for the given compression and version
+pub fn build_header(compression: &CompressionType, version: u16, minor_version: u16) -> [u8; HEADER_SIZE] {
+    let mut header = [0; HEADER_SIZE];
+    header[..4].copy_from_slice(compression.tag());
+    header[4..6].copy_from_slice(&version.to_be_bytes());
+    header[6..].copy_from_slice(&minor_version.to_be_bytes());
+    header
+}
+
+/// Guess the compression from the start of the body, for savegames whose
+/// compression tag does not match what follows it
+pub fn detect_compression(path: &str) -> Option<CompressionType> {
+    let mut file = File::open(path).unwrap();
+    file.seek(SeekFrom::Start(HEADER_SIZE as u64)).unwrap();
+    let mut start = [0; 6];
+    file.read_exact(&mut start).ok()?;
+    if start == [0xFD, b'7', b'z', b'X', b'Z', 0] {
+        Some(CompressionType::Lzma)
+    } else if start[0] & 0x0F == 8 && (start[0] as u16 * 256 + start[1] as u16).is_multiple_of(31) {
+        Some(CompressionType::Zlib)
+    } else if start[..4].iter().all(|b| b.is_ascii_alphanumeric()) || start[..4] == [0; 4] {
+        // an uncompressed body starts with a chunk id or the end marker
+        Some(CompressionType::None)
+    } else {
+        None
+    }
+}
+
+/// The file `patch` would write, built in memory
+pub fn patched(input: &str, header: &[u8; HEADER_SIZE]) -> Result<Vec<u8>, String> {
+    let mut bytes = std::fs::read(input).unwrap();
+    if bytes.len() < HEADER_SIZE {
+        return Err(truncated(bytes.len()));
+    }
+    bytes[..HEADER_SIZE].copy_from_slice(header);
+    Ok(bytes)
+}
+
+/// Write `header` followed by the untouched body of `input`, without
+/// decompressing anything. Patching a file onto itself only rewrites the
+/// header bytes.
+pub fn patch(input: &str, output: &str, header: &[u8; HEADER_SIZE]) {
+    let same_file = match (std::fs::canonicalize(input), std::fs::canonicalize(output)) {
+        (Ok(input), Ok(output)) => input == output,
+        _ => false,
+    };
+    if same_file {
+        let mut file = OpenOptions::new().write(true).open(output).unwrap();
+        file.write_all(header).unwrap();
+        return;
+    }
+    let mut source = File::open(input).unwrap();
+    source.seek(SeekFrom::Start(HEADER_SIZE as u64)).unwrap();
+    let mut target = File::create(output).unwrap();
+    target.write_all(header).unwrap();
+    std::io::copy(&mut source, &mut target).unwrap();
+}
@@ -0,0 +1,18 @@
+pub mod chunk;
+pub mod crash;
+pub mod fixture;
+pub mod gameinfo;
+pub mod header;
+pub mod metrics;
+pub mod minimap;
+#[cfg(feature = "parquet")]
+pub mod parquet_export;
+pub mod preview;
+pub mod reader;
+pub mod registry;
+pub mod report;
+pub mod roundtrip;
+pub mod server;
+pub mod summary;
+pub mod table;
+pub mod timeline;
@@ -1,20 +1,399 @@
-mod reader;
-use reader::Savegame;
+#[cfg(feature = "parquet")]
+use savegame_reader::parquet_export;
+use savegame_reader::reader::{self, Savegame};
+use savegame_reader::{crash, fixture, gameinfo, header, metrics, preview, registry, report, roundtrip, server, summary, timeline};
+use sha2::{Digest, Sha256};
 use std::env;
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
-    if args.len() < 2 {
-        println!("Usage: {} <savegame>", args[1]);
+fn usage(program: &str) {
+    println!("Usage: {} <savegame> [output]", program);
+    println!("       {} serve [--bind 127.0.0.1] [--port 8080] [--watch <dir>] [savegame...]", program);
+    println!("       {} metrics [--bind 127.0.0.1] [--port 9100] [--interval 60] <autosave dir>", program);
+    println!("       {} report [--html <output>] [--markdown <output|->] <savegame>", program);
+    println!("       {} verify <savegame>", program);
+    println!("       {} crash-info <crash.sav>", program);
+    println!("       {} title <opntitle.dat> [output [--verify-roundtrip|--dry-run]]", program);
+    println!("       {} index <savegame>", program);
+    println!("       {} game-info <savegame>", program);
+    println!("       {} set-version <savegame> <output> <version> [--verify-roundtrip|--dry-run]", program);
+    println!("       {} fix-compression <savegame> <output> [--verify-roundtrip|--dry-run]", program);
+    println!(
+        "       {} timeline <dir> [--interval all|monthly|yearly] [--format csv|json] [--output <file>]",
+        program
+    );
+    println!(
+        "       {} generate-fixture <output> [--version 300] [--compression none|zlib|lzma] [--chunks DATE,MAPS,...] [--verify-roundtrip|--dry-run]",
+        program
+    );
+    #[cfg(feature = "parquet")]
+    println!("       {} parquet <savegame> <output dir>", program);
+}
+
+#[cfg(feature = "parquet")]
+fn export_parquet(args: &[String]) {
+    let savegame = Savegame::new(args[0].clone());
+    for path in parquet_export::export(&savegame.chunks(), &args[1]) {
+        println!("Wrote {}", path);
+    }
+}
+
+fn serve(args: &[String]) {
+    let mut bind = "127.0.0.1".to_string();
+    let mut port = 8080;
+    let mut watch = None;
+    let mut paths = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--bind" => bind = args.next().unwrap().clone(),
+            "--port" => port = args.next().unwrap().parse().unwrap(),
+            "--watch" => watch = args.next().cloned(),
+            _ => paths.push(arg.clone()),
+        }
+    }
+    server::serve(&bind, port, &paths, watch.as_deref());
+}
+
+fn serve_metrics(args: &[String]) {
+    let mut bind = "127.0.0.1".to_string();
+    let mut port = 9100;
+    let mut interval = 60;
+    let mut dir = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--bind" => bind = args.next().unwrap().clone(),
+            "--port" => port = args.next().unwrap().parse().unwrap(),
+            "--interval" => interval = args.next().unwrap().parse().unwrap(),
+            _ => dir = Some(arg.clone()),
+        }
+    }
+    let dir = dir.expect("metrics needs the autosave directory");
+    metrics::serve(&bind, port, &dir, std::time::Duration::from_secs(interval));
+}
+
+fn report(args: &[String]) {
+    let mut html = None;
+    let mut markdown = None;
+    let mut path = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--html" => html = args.next().cloned(),
+            "--markdown" => markdown = args.next().cloned(),
+            _ => path = Some(arg.clone()),
+        }
+    }
+    let savegame = Savegame::new(path.expect("report needs a savegame"));
+    let report = report::Report::new(&savegame);
+    if html.is_none() && markdown.is_none() {
+        markdown = Some("-".to_string());
+    }
+    if let Some(output) = html {
+        std::fs::write(&output, report::html(&report)).unwrap();
+        println!("Wrote {}", output);
+    }
+    match markdown.as_deref() {
+        Some("-") => print!("{}", report::markdown(&report)),
+        Some(output) => {
+            std::fs::write(output, report::markdown(&report)).unwrap();
+            println!("Wrote {}", output);
+        }
+        None => {}
+    }
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// List the chunks of a savegame and check them against the chunks OpenTTD
+/// knows for its version, exits with 1 when anything looks off
+fn verify(args: &[String]) {
+    let savegame = Savegame::new(args[0].clone());
+    let chunks = savegame.chunks();
+    println!("Savegame: {}", savegame.path);
+    println!("Version: {}, compression: {:?}", savegame.version, savegame.compression);
+    // digests cover the decompressed data, so they match whatever the compression
+    println!("SHA-256: {}", sha256_hex(&savegame.data));
+    for chunk in chunks.iter() {
+        let known = if registry::known_chunk(&chunk.id).is_some() { "" } else { " (unknown)" };
+        let digest = sha256_hex(savegame.raw_chunk(chunk));
+        println!("  {} {} {:?} {} bytes{}", chunk.id, digest, chunk.chunk_type, chunk.size(), known);
+    }
+    let check = registry::check(savegame.version, &chunks);
+    registry::print_check(savegame.version, &check);
+    if !check.is_clean() {
+        std::process::exit(1);
+    }
+    println!("All chunks are known");
+}
+
+/// Check a title game savegame and optionally write it back out
+fn title(args: &[String]) {
+    let (args, flags) = split_flags(args);
+    let savegame = Savegame::new(args[0].clone());
+    if !savegame.is_title_game() {
+        println!("Note: OpenTTD only loads the title game from files named opntitle.dat");
+    }
+    let chunks = savegame.chunks();
+    println!("Title game: {}", savegame.path);
+    println!("Version: {}, compression: {:?}", savegame.version, savegame.compression);
+    if let Some(date) = summary::date(&chunks) {
+        println!("Date: {}", summary::format_date(date));
+    }
+    if let Some((x, y)) = summary::map_size(&chunks) {
+        println!("Map size: {} x {}", x, y);
+    }
+    println!("Chunks: {}", chunks.len());
+    registry::print_check(savegame.version, &registry::check(savegame.version, &chunks));
+    if let Some(output) = args.get(1) {
+        if flags.contains(&"--dry-run") {
+            println!();
+            preview::print(Some(args[0]), output, &savegame.encode());
+            return;
+        }
+        savegame.write(output.to_string());
+        println!("Wrote {}", output);
+        if flags.contains(&"--verify-roundtrip") {
+            roundtrip::verify_or_exit(&savegame, &savegame, output);
+        }
+    }
+}
+
+/// Split the positional arguments from the `--flags`
+fn split_flags(args: &[String]) -> (Vec<&String>, Vec<&str>) {
+    let (flags, positional): (Vec<&String>, Vec<&String>) = args.iter().partition(|arg| arg.starts_with("--"));
+    (positional, flags.iter().map(|flag| flag.as_str()).collect())
+}
+
+/// Number of positional arguments, flags may come anywhere
+fn positional(args: &[String]) -> usize {
+    split_flags(args).0.len()
+}
+
+/// The value, or print the error and exit with 1
+fn or_exit<T>(result: Result<T, String>) -> T {
+    result.unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    })
+}
+
+/// Change the version in the header, the body is copied without
+/// decompressing it
+fn set_version(args: &[String]) {
+    let (args, flags) = split_flags(args);
+    let version: u16 = args[2].parse().unwrap();
+    let mut patched = or_exit(header::read_header(args[0]));
+    patched[4..6].copy_from_slice(&version.to_be_bytes());
+    if flags.contains(&"--dry-run") {
+        preview::print(Some(args[0]), args[1], &or_exit(header::patched(args[0], &patched)));
+        return;
+    }
+    let verify = flags.contains(&"--verify-roundtrip");
+    // the source has to be read before it is patched in place
+    let original = verify.then(|| Savegame::new(args[0].clone()));
+    header::patch(args[0], args[1], &patched);
+    println!("Wrote {} with version {}", args[1], version);
+    if let Some(original) = original {
+        let mut model = original.clone();
+        model.version = version;
+        roundtrip::verify_or_exit(&original, &model, args[1]);
+    }
+}
+
+/// Correct the compression tag to match the body, the body is copied
+/// without decompressing it
+fn fix_compression(args: &[String]) {
+    let (args, flags) = split_flags(args);
+    let old = or_exit(header::read_header(args[0]));
+    let compression = header::detect_compression(args[0]).expect("Unknown compression");
+    let version = u16::from_be_bytes([old[4], old[5]]);
+    let minor_version = u16::from_be_bytes([old[6], old[7]]);
+    let patched = header::build_header(&compression, version, minor_version);
+    if flags.contains(&"--dry-run") {
+        preview::print(Some(args[0]), args[1], &or_exit(header::patched(args[0], &patched)));
+        return;
+    }
+    let verify = flags.contains(&"--verify-roundtrip");
+    let body = verify.then(|| std::fs::read(args[0]).unwrap()[header::HEADER_SIZE..].to_vec());
+    header::patch(args[0], args[1], &patched);
+    println!(
+        "Wrote {} with compression {:?} (was {})",
+        args[1],
+        compression,
+        String::from_utf8_lossy(&old[..4])
+    );
+    if let Some(body) = body {
+        let (data, error) = reader::decompress(&compression, &body);
+        if let Some(error) = error {
+            panic!("Failed to decompress savegame: {}", error);
+        }
+        let model = Savegame {
+            path: args[0].clone(),
+            data,
+            version,
+            minor_version,
+            compression,
+        };
+        roundtrip::verify_or_exit(&model, &model, args[1]);
+    }
+}
+
+fn timeline(args: &[String]) {
+    let mut interval = timeline::Interval::Monthly;
+    let mut format = "csv".to_string();
+    let mut output = None;
+    let mut dir = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--interval" => {
+                let name = args.next().unwrap();
+                interval = timeline::Interval::parse(name).expect("interval is all, monthly or yearly");
+            }
+            "--format" => format = args.next().unwrap().clone(),
+            "--output" => output = args.next().cloned(),
+            _ => dir = Some(arg.clone()),
+        }
+    }
+    let snapshots = timeline::collect(&dir.expect("timeline needs a directory"), interval);
+    let text = match format.as_str() {
+        "json" => serde_json::to_string_pretty(&timeline::json(&snapshots)).unwrap(),
+        "csv" => timeline::csv(&snapshots),
+        _ => panic!("format is csv or json"),
+    };
+    match output {
+        Some(output) => {
+            std::fs::write(&output, text).unwrap();
+            println!("Wrote {} snapshots to {}", snapshots.len(), output);
+        }
+        None => println!("{}", text),
+    }
+}
+
+/// Write a small synthetic savegame with the chosen version, compression
+/// and chunks
+fn generate_fixture(args: &[String]) {
+    let mut version = 300;
+    let mut compression = reader::CompressionType::Zlib;
+    let mut chunks = None;
+    let mut output = None;
+    let mut dry_run = false;
+    let mut verify = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dry-run" => dry_run = true,
+            "--verify-roundtrip" => verify = true,
+            "--version" => version = args.next().unwrap().parse().unwrap(),
+            "--compression" => {
+                let name = args.next().unwrap();
+                compression = reader::CompressionType::from_name(name).expect("compression is none, zlib or lzma");
+            }
+            "--chunks" => chunks = Some(args.next().unwrap().split(',').collect()),
+            _ if arg.starts_with("--") => panic!("Unknown option {} for generate-fixture", arg),
+            _ if output.is_none() => output = Some(arg.clone()),
+            _ => panic!("generate-fixture writes one output file, {} is one too many", arg),
+        }
+    }
+    let output = output.expect("generate-fixture needs an output file");
+    let chunks: Vec<&str> = chunks.unwrap_or_else(|| fixture::default_chunks(version));
+    let savegame = fixture::standard(version, compression, &chunks).build(&output);
+    if dry_run {
+        preview::print(None, &output, &savegame.encode());
         return;
     }
-    let mut savegame = Savegame::new(args[1].clone());
-    println!("Read savegame: {}", args[1]);
-    let output_path = if args.len() > 2 {
-        args[2].clone()
+    savegame.write(output.clone());
+    println!(
+        "Wrote {} with version {}, compression {:?}, chunks {}",
+        output,
+        savegame.version,
+        savegame.compression,
+        chunks.join(",")
+    );
+    if verify {
+        roundtrip::verify_or_exit(&savegame, &savegame, &output);
+    }
+}
+
+/// Print the chunk index as JSON, offsets are into the decompressed body,
+/// which `<savegame> [output]` writes out
+fn index(args: &[String]) {
+    let savegame = Savegame::new(args[0].clone());
+    let index = savegame.chunk_index().unwrap_or_else(|error| {
+        eprintln!("Corrupt savegame: {}", error);
+        std::process::exit(1);
+    });
+    let entries: Vec<serde_json::Value> = index
+        .iter()
+        .map(|entry| {
+            serde_json::json!({
+                "id": entry.id,
+                "offset": entry.offset,
+                "len": entry.len,
+                "kind": format!("{:?}", entry.kind),
+            })
+        })
+        .collect();
+    println!("{}", serde_json::to_string_pretty(&entries).unwrap());
+}
+
+/// Print the game info a server with this savegame would send to the game
+/// coordinator
+fn game_info(args: &[String]) {
+    let savegame = Savegame::new(args[0].clone());
+    let name = std::path::Path::new(&savegame.path).file_name().unwrap().to_string_lossy().to_string();
+    let info = gameinfo::json(&name, &savegame.chunks());
+    println!("{}", serde_json::to_string_pretty(&info).unwrap());
+}
+
+/// Read a savegame and dump its decompressed body
+/// Dump the decompressed body, which is no savegame file, so the flags of
+/// the commands writing savegames do not apply
+fn resave(args: &[String]) {
+    let (args, flags) = split_flags(args);
+    if let Some(flag) = flags.first() {
+        panic!("Unknown option {}, dumping the decompressed body takes no options", flag);
+    }
+    let savegame = Savegame::new(args[0].clone());
+    if savegame.is_title_game() {
+        println!("Read title game: {}", args[0]);
+    } else {
+        println!("Read savegame: {}", args[0]);
+    }
+    let output_path = if args.len() > 1 {
+        args[1].to_string()
     } else {
         "output_savegame.sav".to_string()
     };
     savegame.save(output_path);
     println!("{}, {}, {}, {:?}", savegame.path, savegame.data.len(), savegame.version, savegame.compression);
 }
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 2 {
+        usage(&args[0]);
+        return;
+    }
+    match args[1].as_str() {
+        #[cfg(feature = "parquet")]
+        "parquet" if args.len() > 3 => export_parquet(&args[2..]),
+        "serve" => serve(&args[2..]),
+        "metrics" => serve_metrics(&args[2..]),
+        "report" => report(&args[2..]),
+        "verify" if args.len() > 2 => verify(&args[2..]),
+        "index" if args.len() > 2 => index(&args[2..]),
+        "game-info" if args.len() > 2 => game_info(&args[2..]),
+        "set-version" if positional(&args[2..]) > 2 => set_version(&args[2..]),
+        "fix-compression" if positional(&args[2..]) > 1 => fix_compression(&args[2..]),
+        "set-version" | "fix-compression" => usage(&args[0]),
+        "timeline" => timeline(&args[2..]),
+        "generate-fixture" if args.len() > 2 => generate_fixture(&args[2..]),
+        "title" if args.len() > 2 => title(&args[2..]),
+        "crash-info" if args.len() > 2 => crash::print(args[2].clone()),
+        _ => resave(&args[1..]),
+    }
+}
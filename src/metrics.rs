@@ -0,0 +1,128 @@
+use crate::chunk::{find, read_chunks_lenient, Chunk};
+use crate::reader::Savegame;
+use crate::server::newest_savegame;
+use crate::summary::{companies, vehicle_counts, Company};
+use std::fmt::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tiny_http::{Header, Response, Server};
+
+fn escape(label: &str) -> String {
+    label
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// name, help text and value of a gauge exported per company
+type CompanyGauge = (&'static str, &'static str, fn(&Company) -> i64);
+
+fn gauge(out: &mut String, name: &str, help: &str) {
+    writeln!(out, "# HELP {} {}", name, help).unwrap();
+    writeln!(out, "# TYPE {} gauge", name).unwrap();
+}
+
+/// Read a savegame for `render`, a half written autosave is an error
+/// instead of a panic
+fn load(path: &Path) -> Result<(Savegame, Vec<Chunk>), String> {
+    let (savegame, error) = Savegame::open_lenient(path.display().to_string())?;
+    if let Some(error) = error {
+        return Err(error);
+    }
+    let scan = read_chunks_lenient(&savegame.data);
+    if let Some(error) = scan.error {
+        return Err(error);
+    }
+    // the chunks `render` decodes
+    for id in ["PLYR", "VEHS"] {
+        if let Some(chunk) = find(&scan.chunks, id) {
+            chunk.try_records().map_err(|error| format!("chunk {}: {}", id, error))?;
+        }
+    }
+    Ok((savegame, scan.chunks))
+}
+
+/// Render the gauges of one savegame in the Prometheus text format
+pub fn render(savegame: &Savegame, chunks: &[Chunk]) -> String {
+    let companies = companies(chunks);
+    let mut out = String::new();
+
+    gauge(&mut out, "openttd_savegame_version", "Savegame version");
+    writeln!(out, "openttd_savegame_version {}", savegame.version).unwrap();
+
+    let company_gauges: [CompanyGauge; 3] = [
+        ("openttd_company_money", "Company bank balance", |c| c.money),
+        ("openttd_company_loan", "Company loan", |c| c.loan),
+        (
+            "openttd_company_cargo_delivered",
+            "Cargo delivered in the current period, all cargo types",
+            |c| c.delivered,
+        ),
+    ];
+    for (name, help, value) in company_gauges {
+        gauge(&mut out, name, help);
+        for company in companies.iter() {
+            writeln!(
+                out,
+                "{}{{company=\"{}\",name=\"{}\"}} {}",
+                name,
+                company.id,
+                escape(&company.name),
+                value(company)
+            )
+            .unwrap();
+        }
+    }
+
+    gauge(&mut out, "openttd_vehicles", "Number of vehicles");
+    for ((owner, vehicle_type), count) in vehicle_counts(chunks) {
+        writeln!(
+            out,
+            "openttd_vehicles{{company=\"{}\",type=\"{}\"}} {}",
+            owner, vehicle_type, count
+        )
+        .unwrap();
+    }
+
+    out
+}
+
+/// Parse the newest savegame in `dir` every `interval` and serve the
+/// result on `/metrics`, reachable on `bind` only
+pub fn serve(bind: &str, port: u16, dir: &str, interval: Duration) {
+    let metrics = Arc::new(Mutex::new(String::new()));
+    let updater = Arc::clone(&metrics);
+    let dir = dir.to_string();
+    thread::spawn(move || {
+        let mut last = None;
+        loop {
+            if let Some(path) = newest_savegame(&dir) {
+                let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+                if last != Some((path.clone(), modified)) {
+                    match load(&path) {
+                        Ok((savegame, chunks)) => {
+                            *updater.lock().unwrap() = render(&savegame, &chunks);
+                            last = Some((path, modified));
+                        }
+                        Err(error) => eprintln!("Failed to load {}: {}", path.display(), error),
+                    }
+                }
+            }
+            thread::sleep(interval);
+        }
+    });
+
+    let server = Server::http((bind, port)).unwrap();
+    println!("Serving metrics on http://{}:{}/metrics", bind, port);
+    let content_type = Header::from_bytes("Content-Type", "text/plain; version=0.0.4").unwrap();
+    for request in server.incoming_requests() {
+        let response = if request.url() == "/metrics" {
+            Response::from_string(metrics.lock().unwrap().clone())
+        } else {
+            Response::from_string("not found").with_status_code(404)
+        };
+        let _ = request.respond(response.with_header(content_type.clone()));
+    }
+}
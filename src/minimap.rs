@@ -0,0 +1,49 @@
+use crate::chunk::{find, Chunk};
+use crate::summary::map_size;
+
+/// Colour per tile type, the upper nibble of the MAPT bytes
+const TILE_COLOURS: [[u8; 3]; 16] = [
+    [112, 160, 64],  // clear
+    [96, 96, 96],    // rail
+    [64, 64, 64],    // road
+    [192, 96, 64],   // house
+    [48, 112, 32],   // trees
+    [224, 200, 64],  // station
+    [48, 96, 192],   // water
+    [0, 0, 0],       // void
+    [160, 64, 160],  // industry
+    [128, 128, 128], // tunnel/bridge
+    [176, 144, 112], // object
+    [255, 0, 255],
+    [255, 0, 255],
+    [255, 0, 255],
+    [255, 0, 255],
+    [255, 0, 255],
+];
+
+/// Render one pixel per tile, coloured by tile type and brightened with
+/// height, returns the PNG file contents
+pub fn render_png(chunks: &[Chunk]) -> Option<Vec<u8>> {
+    let (width, height) = map_size(chunks)?;
+    let types = &find(chunks, "MAPT")?.data;
+    let heights = find(chunks, "MAPH").map(|c| &c.data[..]);
+    if types.len() != (width * height) as usize {
+        return None;
+    }
+    let mut pixels = Vec::with_capacity(types.len() * 3);
+    for (i, tile) in types.iter().enumerate() {
+        let level = heights.and_then(|h| h.get(i)).copied().unwrap_or(0) as u16;
+        for channel in TILE_COLOURS[(tile >> 4) as usize] {
+            pixels.push((channel as u16 + level * 4).min(255) as u8);
+        }
+    }
+
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, width, height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().unwrap();
+    writer.write_image_data(&pixels).unwrap();
+    writer.finish().unwrap();
+    Some(png)
+}
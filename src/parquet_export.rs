@@ -0,0 +1,87 @@
+use crate::chunk::Chunk;
+use crate::table::{read_header, Field, Value, FILE_I16, FILE_I32, FILE_I64, FILE_I8};
+use arrow::array::{ArrayRef, BinaryArray, Int64Array, StringArray, UInt32Array, UInt64Array};
+use arrow::datatypes::{DataType, Field as ArrowField, Schema};
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Build a column from one field of every record, numbers keep their type,
+/// strings stay as they are and lists and structs become JSON text
+fn field_column(field: &Field, records: &[Value]) -> (DataType, ArrayRef) {
+    let values = records.iter().map(|record| record.get(&field.key));
+    if field.is_scalar() && matches!(field.base_type(), FILE_I8 | FILE_I16 | FILE_I32 | FILE_I64) {
+        let column: Int64Array = values.map(|v| v.and_then(Value::as_i64)).collect();
+        (DataType::Int64, Arc::new(column))
+    } else if field.is_scalar() {
+        let column: UInt64Array = values
+            .map(|v| match v {
+                Some(Value::UInt(value)) => Some(*value),
+                _ => None,
+            })
+            .collect();
+        (DataType::UInt64, Arc::new(column))
+    } else {
+        let column: StringArray = values
+            .map(|v| match v {
+                Some(Value::Str(value)) => Some(value.clone()),
+                Some(value) => Some(value.to_json().to_string()),
+                None => None,
+            })
+            .collect();
+        (DataType::Utf8, Arc::new(column))
+    }
+}
+
+fn chunk_batch(chunk: &Chunk) -> RecordBatch {
+    let indices: UInt32Array = chunk.elements.iter().map(|e| e.index).collect();
+    let mut fields = vec![ArrowField::new("index", DataType::UInt32, false)];
+    let mut columns: Vec<ArrayRef> = vec![Arc::new(indices)];
+    match &chunk.header {
+        Some(header) => {
+            let header = read_header(header).unwrap_or_else(|error| panic!("Corrupt chunk {}: {}", chunk.id, error));
+            let records: Vec<Value> = chunk.records().into_iter().map(|(_, record)| record).collect();
+            for field in header.iter() {
+                let (data_type, column) = field_column(field, &records);
+                fields.push(ArrowField::new(&field.key, data_type, true));
+                columns.push(column);
+            }
+        }
+        None => {
+            // pre-table savegames carry no field description, keep the raw bytes
+            let data: BinaryArray = chunk.elements.iter().map(|e| Some(&e.data[..])).collect();
+            fields.push(ArrowField::new("data", DataType::Binary, false));
+            columns.push(Arc::new(data));
+        }
+    }
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).unwrap()
+}
+
+/// File name for a chunk, ids come straight from the savegame so anything
+/// but 4 letters or digits is hex encoded to stay inside the output directory
+fn file_name(id: &str) -> String {
+    if id.len() == 4 && id.bytes().all(|b| b.is_ascii_alphanumeric()) {
+        format!("{}.parquet", id)
+    } else {
+        let hex: String = id.bytes().map(|b| format!("{:02x}", b)).collect();
+        format!("chunk-{}.parquet", hex)
+    }
+}
+
+/// Write every list chunk as `<dir>/<ID>.parquet`
+pub fn export(chunks: &[Chunk], dir: &str) -> Vec<String> {
+    std::fs::create_dir_all(dir).unwrap();
+    let mut written = Vec::new();
+    for chunk in chunks.iter().filter(|c| c.chunk_type.is_list()) {
+        let batch = chunk_batch(chunk);
+        let path = Path::new(dir).join(file_name(&chunk.id));
+        let file = File::create(&path).unwrap();
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        written.push(path.display().to_string());
+    }
+    written
+}
@@ -0,0 +1,126 @@
+use crate::chunk::{read_chunks_lenient, Chunk};
+use crate::header::HEADER_SIZE;
+use crate::reader::Savegame;
+use std::collections::HashMap;
+
+fn describe_header(bytes: &[u8]) -> String {
+    if bytes.len() < HEADER_SIZE {
+        return "no header".to_string();
+    }
+    format!(
+        "{} version {}.{}",
+        String::from_utf8_lossy(&bytes[..4]),
+        u16::from_be_bytes([bytes[4], bytes[5]]),
+        u16::from_be_bytes([bytes[6], bytes[7]])
+    )
+}
+
+/// Decode a savegame file held in memory, or say why it can not be read
+fn decode(path: &str, bytes: &[u8]) -> Result<(Savegame, Vec<Chunk>), String> {
+    let path = path.to_string();
+    let (savegame, error) = match Savegame::from_bytes(path, bytes) {
+        Ok(result) => result,
+        Err(error) => return Err(format!("has an unreadable header: {}", error)),
+    };
+    if let Some(error) = error {
+        return Err(format!("does not decompress: {}", error));
+    }
+    let scan = read_chunks_lenient(&savegame.data);
+    if let Some(error) = scan.error {
+        return Err(format!("is corrupt: {}", error));
+    }
+    Ok((savegame, scan.chunks))
+}
+
+/// How two versions of a chunk differ: the number of records for table
+/// chunks, RIFF and array chunks or records that do not decode can only be
+/// told apart by their bytes
+fn difference(old: &Chunk, new: &Chunk) -> String {
+    let (Ok(old_records), Ok(new_records)) = (old.try_records(), new.try_records()) else {
+        return "contents differ".to_string();
+    };
+    if old_records.is_empty() && new_records.is_empty() {
+        return "contents differ".to_string();
+    }
+    let old_records: HashMap<u32, _> = old_records.into_iter().collect();
+    let new_records: HashMap<u32, _> = new_records.into_iter().collect();
+    let differing = new_records
+        .iter()
+        .filter(|(index, record)| old_records.get(index) != Some(record))
+        .count()
+        + old_records.keys().filter(|index| !new_records.contains_key(index)).count();
+    format!("{} records differ", differing)
+}
+
+/// Print what writing `bytes` to `output` would change compared to `input`,
+/// for `--dry-run`. The bytes are decoded the same way a written savegame
+/// would be read back.
+pub fn print(input: Option<&str>, output: &str, bytes: &[u8]) {
+    println!("Dry run, {} was not written", output);
+    let before = input.map(|path| (path, std::fs::read(path).unwrap()));
+    match &before {
+        Some((path, old)) => println!("Size: {} bytes, {} has {} bytes", bytes.len(), path, old.len()),
+        None => println!("Size: {} bytes", bytes.len()),
+    }
+    let new_header = describe_header(bytes);
+    match &before {
+        Some((_, old)) if describe_header(old) != new_header => {
+            println!("Header: {} -> {}", describe_header(old), new_header)
+        }
+        _ => println!("Header: {}", new_header),
+    }
+
+    let (after, after_chunks) = match decode(output, bytes) {
+        Ok(decoded) => decoded,
+        Err(error) => {
+            println!("Output {}", error);
+            return;
+        }
+    };
+    let (source, source_chunks) = match &before {
+        Some((path, old)) => match decode(path, old) {
+            Ok((source, chunks)) => (Some(source), chunks),
+            Err(error) => {
+                println!("Input {}, chunks are not compared", error);
+                println!("Chunks: {}", after_chunks.len());
+                return;
+            }
+        },
+        None => (None, Vec::new()),
+    };
+
+    let (mut unchanged, mut changed, mut added, mut removed) = (0, 0, 0, 0);
+    let mut changes = Vec::new();
+    for chunk in after_chunks.iter() {
+        let old = source
+            .as_ref()
+            .and_then(|source| source_chunks.iter().find(|c| c.id == chunk.id).map(|c| (source, c)));
+        match old {
+            None => {
+                added += 1;
+                changes.push(format!("  + {} {:?} {} bytes", chunk.id, chunk.chunk_type, chunk.size()));
+            }
+            Some((source, old)) if source.raw_chunk(old) == after.raw_chunk(chunk) => unchanged += 1,
+            Some((_, old)) => {
+                changed += 1;
+                changes.push(format!(
+                    "  ~ {} {} -> {} bytes, {}",
+                    chunk.id,
+                    old.size(),
+                    chunk.size(),
+                    difference(old, chunk)
+                ));
+            }
+        }
+    }
+    for old in source_chunks.iter() {
+        if !after_chunks.iter().any(|c| c.id == old.id) {
+            removed += 1;
+            changes.push(format!("  - {} {:?} {} bytes", old.id, old.chunk_type, old.size()));
+        }
+    }
+    println!("Chunks: {} unchanged, {} changed, {} added, {} removed", unchanged, changed, added, removed);
+    for change in changes {
+        println!("{}", change);
+    }
+}
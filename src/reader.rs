@@ -1,8 +1,10 @@
+use crate::chunk::{chunk_index, read_chunks, Chunk, ChunkEntry};
 use std::fs::File;
 use std::io::Read;
 use std::io::Write;
 
-trait Reader {
+pub trait Reader {
+    fn position(&self) -> usize;
     fn load(&self, start: usize, end: usize) -> &[u8];
     fn read_byte(&mut self) -> u8;
     fn read(&mut self, len: usize) -> &[u8];
@@ -21,25 +23,22 @@ trait Reader {
 }
 
 struct FileReader {
-    path: String,
     data: Vec<u8>,
     position: usize,
 }
 
 impl FileReader {
-    fn new(path: String) -> Self {
-        let mut file = File::open(&path).unwrap();
-        let mut data = Vec::new();
-        file.read_to_end(&mut data).unwrap();
-        FileReader {
-            path: path,
-            data: data,
-            position: 0,
-        }
+    fn new(path: &str) -> Result<Self, String> {
+        let data = std::fs::read(path).map_err(|error| format!("Failed to read {}: {}", path, error))?;
+        Ok(FileReader { data, position: 0 })
     }
 }
 
 impl Reader for FileReader {
+    fn position(&self) -> usize {
+        self.position
+    }
+
     fn load(&self, start: usize, end: usize) -> &[u8] {
         &self.data[start..end]
     }
@@ -113,27 +112,29 @@ impl Reader for FileReader {
     }
 }
 
-struct DataReader {
-    data: Vec<u8>,
+/// Reads from data in memory, owned (`Vec<u8>`) or borrowed (`&[u8]`)
+pub struct DataReader<T = Vec<u8>> {
+    data: T,
     position: usize,
 }
 
-impl DataReader {
-    fn new(data: Vec<u8>) -> Self {
-        DataReader {
-            data: data,
-            position: 0,
-        }
+impl<T: AsRef<[u8]>> DataReader<T> {
+    pub fn new(data: T) -> Self {
+        DataReader { data, position: 0 }
     }
 }
 
-impl Reader for DataReader {
+impl<T: AsRef<[u8]>> Reader for DataReader<T> {
+    fn position(&self) -> usize {
+        self.position
+    }
+
     fn load(&self, start: usize, end: usize) -> &[u8] {
-        &self.data[start..end]
+        &self.data.as_ref()[start..end]
     }
 
     fn read_byte(&mut self) -> u8 {
-        let byte = self.data[self.position];
+        let byte = self.data.as_ref()[self.position];
         self.position += 1;
         byte
     }
@@ -141,16 +142,16 @@ impl Reader for DataReader {
     fn read(&mut self, len: usize) -> &[u8] {
         let start = self.position;
         self.position += len;
-        &self.data[start..self.position]
+        &self.data.as_ref()[start..self.position]
     }
 
     fn read_leftover(&self) -> &[u8] {
         let start = self.position;
-        &self.data[start..]
+        &self.data.as_ref()[start..]
     }
 
     fn read_all(&self) -> &[u8] {
-        &self.data
+        self.data.as_ref()
     }
 
     fn read_u8(&mut self) -> u8 {
@@ -201,71 +202,201 @@ impl Reader for DataReader {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum CompressionType {
     None,
     Zlib,
     Lzma,
 }
 
+impl CompressionType {
+    pub fn tag(&self) -> &'static [u8; 4] {
+        match self {
+            CompressionType::None => b"OTTN",
+            CompressionType::Zlib => b"OTTZ",
+            CompressionType::Lzma => b"OTTX",
+        }
+    }
+
+    fn from_tag(tag: &[u8]) -> Result<Self, String> {
+        match tag {
+            b"OTTN" => Ok(CompressionType::None),
+            b"OTTZ" => Ok(CompressionType::Zlib),
+            b"OTTX" => Ok(CompressionType::Lzma),
+            b"OTTD" => Err("LZO compression is unsupported".to_string()),
+            _ => Err(format!("Unknown compression type {:?}", String::from_utf8_lossy(tag))),
+        }
+    }
+
+    /// none, zlib or lzma, as in OpenTTD's savegame_format setting
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "none" => Some(CompressionType::None),
+            "zlib" => Some(CompressionType::Zlib),
+            "lzma" => Some(CompressionType::Lzma),
+            _ => None,
+        }
+    }
+}
+
 /// case OTTN: no decompression, return the data as is
-fn decompress_none(data: &[u8]) -> Vec<u8> {
-    data.to_vec()
+fn decompress_none(data: &[u8]) -> (Vec<u8>, Option<String>) {
+    (data.to_vec(), None)
 }
 
-/// case OTTZ: zlib decompression, return the decompressed data
-fn decompress_zlib(data: &[u8]) -> Vec<u8> {
+/// case OTTZ: zlib decompression, return the decompressed data and the
+/// error that stopped decompression early, if any
+fn decompress_zlib(data: &[u8]) -> (Vec<u8>, Option<String>) {
     use flate2::read::ZlibDecoder;
 
     let mut decoder = ZlibDecoder::new(data);
     let mut decompressed = Vec::new();
-    decoder.read_to_end(&mut decompressed).unwrap();
-    decompressed
+    let error = decoder.read_to_end(&mut decompressed).err();
+    (decompressed, error.map(|e| e.to_string()))
 }
 
-/// case OTTX: lzma decompression, return the decompressed data
-fn decompress_lzma(data: &[u8]) -> Vec<u8> {
+/// case OTTX: lzma decompression, return the decompressed data and the
+/// error that stopped decompression early, if any
+fn decompress_lzma(data: &[u8]) -> (Vec<u8>, Option<String>) {
     use xz2::read::XzDecoder;
 
     let mut decoder = XzDecoder::new(data);
     let mut decompressed = Vec::new();
-    decoder.read_to_end(&mut decompressed).unwrap();
-    decompressed
+    let error = decoder.read_to_end(&mut decompressed).err();
+    (decompressed, error.map(|e| e.to_string()))
+}
+
+/// decompress a savegame body, returns what could be decompressed and the
+/// error that stopped it early, if any
+pub fn decompress(compression: &CompressionType, data: &[u8]) -> (Vec<u8>, Option<String>) {
+    match compression {
+        CompressionType::None => decompress_none(data),
+        CompressionType::Zlib => decompress_zlib(data),
+        CompressionType::Lzma => decompress_lzma(data),
+    }
 }
 
-#[derive(Debug)]
+/// compress the savegame body the way OpenTTD does by default
+fn compress(compression: &CompressionType, data: &[u8]) -> Vec<u8> {
+    match compression {
+        CompressionType::None => data.to_vec(),
+        CompressionType::Zlib => {
+            use flate2::write::ZlibEncoder;
+
+            let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::new(6));
+            encoder.write_all(data).unwrap();
+            encoder.finish().unwrap()
+        }
+        CompressionType::Lzma => {
+            use xz2::write::XzEncoder;
+
+            let mut encoder = XzEncoder::new(Vec::new(), 2);
+            encoder.write_all(data).unwrap();
+            encoder.finish().unwrap()
+        }
+    }
+}
+
+/// compression, version and minor version from the 8 byte header
+fn read_header(reader: &mut impl Reader) -> Result<(CompressionType, u16, u16), String> {
+    let len = reader.read_all().len();
+    if len < 8 {
+        return Err(format!("Header truncated, the file has only {} bytes", len));
+    }
+    let compression = CompressionType::from_tag(reader.read(4))?;
+    Ok((compression, reader.read_u16(), reader.read_u16()))
+}
+
+#[derive(Debug, Clone)]
 pub struct Savegame {
     pub path: String,
     pub data: Vec<u8>,
     pub version: u16,
+    /// the two bytes after the version, only old savegames use them
+    pub minor_version: u16,
     pub compression: CompressionType,
 }
 
 impl Savegame {
 
     pub fn new(path: String) -> Self {
-        let mut reader = FileReader::new(path.clone());
-        let compression = match reader.read(4) {
-            b"OTTN" => CompressionType::None,
-            b"OTTZ" => CompressionType::Zlib,
-            b"OTTX" => CompressionType::Lzma,
-            b"OTTD" => panic!("LZO compression is unsupported"),
-            _ => panic!("Unknown compression type"),
-        };
-        let version = reader.read_u16();
-        reader.read(2); // skip 2 bytes
+        let (savegame, error) = Savegame::open_lenient(path).unwrap_or_else(|error| panic!("{}", error));
+        if let Some(error) = error {
+            panic!("Failed to decompress savegame: {}", error);
+        }
+        savegame
+    }
+
+    /// Open a savegame keeping whatever could be decompressed, for damaged
+    /// files like crash saves, the decompression error is returned as well.
+    /// Only a file that can not be read or a truncated or unknown header is an
+    /// error.
+    pub fn open_lenient(path: String) -> Result<(Self, Option<String>), String> {
+        let mut reader = FileReader::new(&path)?;
+        let (compression, version, minor_version) = read_header(&mut reader)?;
         let data = reader.read_leftover();
-        let data = match compression {
-            CompressionType::None => decompress_none(data),
-            CompressionType::Zlib => decompress_zlib(data),
-            CompressionType::Lzma => decompress_lzma(data),
+        let (data, error) = decompress(&compression, data);
+        let savegame = Savegame {
+            path,
+            compression,
+            version,
+            minor_version,
+            data,
         };
-        Savegame {
-            path: path,
-            compression: compression,
-            version: version,
-            data: data,
-        }
+        Ok((savegame, error))
+    }
+
+    /// Like `open_lenient`, for a complete savegame file already in memory
+    pub fn from_bytes(path: String, bytes: &[u8]) -> Result<(Self, Option<String>), String> {
+        let mut reader = DataReader::new(bytes);
+        let (compression, version, minor_version) = read_header(&mut reader)?;
+        let (data, error) = decompress(&compression, reader.read_leftover());
+        let savegame = Savegame {
+            path,
+            compression,
+            version,
+            minor_version,
+            data,
+        };
+        Ok((savegame, error))
+    }
+
+    pub fn chunks(&self) -> Vec<Chunk> {
+        read_chunks(&self.data)
+    }
+
+    /// The bytes of `chunk` in `data`, framing included
+    pub fn raw_chunk(&self, chunk: &Chunk) -> &[u8] {
+        &self.data[chunk.offset..chunk.offset + chunk.len]
+    }
+
+    /// Offsets of the chunks in `data` without decoding their contents,
+    /// corrupt framing is an error
+    pub fn chunk_index(&self) -> Result<Vec<ChunkEntry>, String> {
+        chunk_index(&self.data)
+    }
+
+    /// The title screen savegame, it shares the container with normal
+    /// savegames but OpenTTD only looks for it under this name
+    pub fn is_title_game(&self) -> bool {
+        std::path::Path::new(&self.path)
+            .file_name()
+            .is_some_and(|name| name.eq_ignore_ascii_case("opntitle.dat"))
+    }
+
+    /// The complete savegame file, header included, with the same compression
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = self.compression.tag().to_vec();
+        bytes.extend_from_slice(&self.version.to_be_bytes());
+        bytes.extend_from_slice(&self.minor_version.to_be_bytes());
+        bytes.extend_from_slice(&compress(&self.compression, &self.data));
+        bytes
+    }
+
+    /// Write the savegame as `encode` builds it
+    pub fn write(&self, path: String) {
+        let mut file = File::create(path).unwrap();
+        file.write_all(&self.encode()).unwrap();
     }
 
     pub fn save(&self, path: String) {
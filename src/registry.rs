@@ -0,0 +1,166 @@
+use crate::chunk::Chunk;
+
+/// A chunk OpenTTD knows how to load
+pub struct KnownChunk {
+    pub id: &'static str,
+    /// first savegame version writing the chunk
+    pub since: u16,
+    /// savegame version from which the chunk is no longer written
+    pub until: Option<u16>,
+    /// every savegame in the version range has the chunk
+    pub required: bool,
+}
+
+const fn chunk(id: &'static str, since: u16, until: Option<u16>, required: bool) -> KnownChunk {
+    KnownChunk {
+        id,
+        since,
+        until,
+        required,
+    }
+}
+
+/// Chunk handlers of OpenTTD, removed chunks are still loadable but no
+/// longer written
+pub const KNOWN_CHUNKS: &[KnownChunk] = &[
+    // map
+    chunk("MAPS", 0, None, true),
+    chunk("MAPT", 0, None, true),
+    chunk("MAPH", 194, None, true),
+    chunk("MAPO", 0, None, true),
+    chunk("MAP2", 0, None, true),
+    chunk("M3LO", 0, None, true),
+    chunk("M3HI", 0, None, true),
+    chunk("MAP5", 0, None, true),
+    chunk("MAPE", 42, None, true),
+    chunk("MAP7", 0, None, false),
+    chunk("MAP8", 0, None, false),
+    // water regions, 14.0
+    chunk("WRGN", 324, None, false),
+    // misc
+    chunk("DATE", 0, None, true),
+    chunk("VIEW", 0, None, true),
+    chunk("OPTS", 0, None, false),
+    chunk("PATS", 97, None, true),
+    chunk("CHTS", 0, None, true),
+    chunk("ANIT", 0, None, true),
+    chunk("NAME", 0, Some(84), false),
+    chunk("SIGN", 0, None, true),
+    chunk("GLOG", 98, None, true),
+    chunk("NGRF", 41, None, true),
+    // companies and vehicles
+    chunk("PLYR", 0, None, true),
+    chunk("VEHS", 0, None, true),
+    chunk("ORDR", 0, None, true),
+    chunk("ORDL", 105, None, false),
+    chunk("BKOR", 0, None, false),
+    chunk("DEPT", 0, None, true),
+    chunk("GRPS", 60, None, false),
+    chunk("ENGN", 0, None, true),
+    chunk("ENGS", 0, None, false),
+    chunk("EIDS", 0, None, false),
+    chunk("ERNW", 0, None, false),
+    // stations, towns and industries
+    chunk("STNS", 0, Some(123), false),
+    chunk("STNN", 123, None, true),
+    chunk("ROAD", 0, None, true),
+    chunk("PSAC", 0, None, false),
+    chunk("CITY", 0, None, true),
+    chunk("HIDS", 0, None, false),
+    chunk("INDY", 0, None, true),
+    chunk("IIDS", 0, None, false),
+    chunk("TIDS", 0, None, false),
+    chunk("IBLD", 0, None, false),
+    chunk("ITBL", 0, None, false),
+    chunk("OBJS", 0, None, false),
+    chunk("OBID", 0, None, false),
+    chunk("APID", 0, None, false),
+    chunk("ATID", 0, None, false),
+    chunk("RAIL", 0, None, false),
+    chunk("ROTT", 0, None, false),
+    // economy and cargo
+    chunk("CAPA", 68, None, true),
+    chunk("CAPY", 0, None, false),
+    chunk("CAPR", 0, None, false),
+    chunk("ECMY", 0, None, false),
+    chunk("PRIC", 0, Some(126), false),
+    chunk("SUBS", 0, None, false),
+    chunk("CMDL", 0, None, false),
+    chunk("CMPU", 0, None, false),
+    // cargodist, 1.4
+    chunk("LGRP", 183, None, false),
+    chunk("LGRJ", 183, None, false),
+    chunk("LGRS", 183, None, false),
+    // scripts, goals and story book
+    chunk("AIPL", 0, None, false),
+    // game scripts, 1.2
+    chunk("GSDT", 158, None, false),
+    chunk("GSTR", 158, None, false),
+    chunk("GOAL", 158, None, false),
+    // story book, 1.4
+    chunk("STPE", 185, None, false),
+    chunk("STPA", 185, None, false),
+    // league tables, 13.0
+    chunk("LEAE", 300, None, false),
+    chunk("LEAT", 300, None, false),
+];
+
+impl KnownChunk {
+    /// whether savegames of `version` are written with the chunk
+    pub fn written_by(&self, version: u16) -> bool {
+        version >= self.since && self.until.is_none_or(|until| version < until)
+    }
+}
+
+pub fn known_chunk(id: &str) -> Option<&'static KnownChunk> {
+    KNOWN_CHUNKS.iter().find(|c| c.id == id)
+}
+
+#[derive(Debug, Default)]
+pub struct ChunkCheck {
+    /// ids OpenTTD has no handler for
+    pub unknown: Vec<String>,
+    /// known chunks that this savegame version does not write yet or no
+    /// longer writes
+    pub unexpected: Vec<String>,
+    /// chunks every savegame of this version has, but this one lacks
+    pub missing: Vec<&'static str>,
+}
+
+impl ChunkCheck {
+    pub fn is_clean(&self) -> bool {
+        self.unknown.is_empty() && self.unexpected.is_empty() && self.missing.is_empty()
+    }
+}
+
+/// Print every list of the check that is not empty
+pub fn print_check(version: u16, check: &ChunkCheck) {
+    if !check.unknown.is_empty() {
+        println!("Unknown chunks: {}", check.unknown.join(", "));
+    }
+    if !check.unexpected.is_empty() {
+        println!("Chunks not written by version {}: {}", version, check.unexpected.join(", "));
+    }
+    if !check.missing.is_empty() {
+        println!("Missing chunks: {}", check.missing.join(", "));
+    }
+}
+
+/// Compare the chunks of a savegame against the registry, anything
+/// unknown is the first hint of a modified client
+pub fn check(version: u16, chunks: &[Chunk]) -> ChunkCheck {
+    let mut result = ChunkCheck::default();
+    for chunk in chunks {
+        match known_chunk(&chunk.id) {
+            None => result.unknown.push(chunk.id.clone()),
+            Some(known) if !known.written_by(version) => result.unexpected.push(chunk.id.clone()),
+            Some(_) => {}
+        }
+    }
+    for known in KNOWN_CHUNKS {
+        if known.required && known.written_by(version) && !chunks.iter().any(|c| c.id == known.id) {
+            result.missing.push(known.id);
+        }
+    }
+    result
+}
@@ -0,0 +1,203 @@
+use crate::chunk::Chunk;
+use crate::minimap;
+use crate::reader::Savegame;
+use crate::summary::{companies, count, date, format_date, map_size, newgrfs, vehicle_counts};
+use crate::summary::{Company, NewGrf};
+use std::fmt::Write;
+
+pub const COMPANY_HEADERS: [&str; 6] = ["#", "Name", "President", "Money", "Loan", "Delivered"];
+pub const NEWGRF_HEADERS: [&str; 4] = ["GRF ID", "File", "Version", "MD5"];
+
+/// Everything shown in a report, gathered once so every output format
+/// shows the same numbers
+pub struct Report {
+    pub path: String,
+    pub version: u16,
+    pub compression: String,
+    pub date: Option<(i64, u32, u32)>,
+    pub map_size: Option<(u32, u32)>,
+    pub stats: Vec<(&'static str, usize)>,
+    pub companies: Vec<Company>,
+    pub newgrfs: Vec<NewGrf>,
+    pub chunks: Vec<Chunk>,
+}
+
+impl Report {
+    pub fn new(savegame: &Savegame) -> Self {
+        let chunks = savegame.chunks();
+        let vehicles = vehicle_counts(&chunks).values().sum();
+        let stats = vec![
+            ("Companies", count(&chunks, "PLYR")),
+            ("Vehicles", vehicles),
+            ("Towns", count(&chunks, "CITY")),
+            ("Stations", count(&chunks, "STNN")),
+            ("Industries", count(&chunks, "INDY")),
+            ("Chunks", chunks.len()),
+        ];
+        Report {
+            path: savegame.path.clone(),
+            version: savegame.version,
+            compression: format!("{:?}", savegame.compression),
+            date: date(&chunks),
+            map_size: map_size(&chunks),
+            stats,
+            companies: companies(&chunks),
+            newgrfs: newgrfs(&chunks),
+            chunks,
+        }
+    }
+
+    pub fn date_string(&self) -> String {
+        self.date.map_or("unknown".to_string(), format_date)
+    }
+
+    pub fn map_size_string(&self) -> String {
+        match self.map_size {
+            Some((x, y)) => format!("{} x {}", x, y),
+            None => "unknown".to_string(),
+        }
+    }
+
+    /// (label, value) rows of the summary table
+    pub fn summary(&self) -> Vec<(String, String)> {
+        let mut rows = vec![
+            ("Savegame".to_string(), self.path.clone()),
+            ("Version".to_string(), self.version.to_string()),
+            ("Compression".to_string(), self.compression.clone()),
+            ("Date".to_string(), self.date_string()),
+            ("Map size".to_string(), self.map_size_string()),
+        ];
+        for (label, value) in self.stats.iter() {
+            rows.push((label.to_string(), value.to_string()));
+        }
+        rows
+    }
+
+    pub fn company_rows(&self) -> Vec<Vec<String>> {
+        self.companies
+            .iter()
+            .map(|c| {
+                vec![
+                    c.id.to_string(),
+                    c.name.clone(),
+                    c.president.clone(),
+                    c.money.to_string(),
+                    c.loan.to_string(),
+                    c.delivered.to_string(),
+                ]
+            })
+            .collect()
+    }
+
+    pub fn newgrf_rows(&self) -> Vec<Vec<String>> {
+        self.newgrfs
+            .iter()
+            .map(|g| vec![g.grfid_hex(), g.filename.clone(), g.version.to_string(), g.md5sum.clone()])
+            .collect()
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for group in data.chunks(3) {
+        let bytes = [group[0], *group.get(1).unwrap_or(&0), *group.get(2).unwrap_or(&0)];
+        let bits = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        for i in 0..4 {
+            if i <= group.len() {
+                out.push(ALPHABET[(bits >> (18 - i * 6) & 0x3F) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+fn html_table(out: &mut String, headers: &[&str], rows: &[Vec<String>]) {
+    out.push_str("<table>\n");
+    if !headers.is_empty() {
+        out.push_str("<tr>");
+        for header in headers {
+            write!(out, "<th>{}</th>", escape_html(header)).unwrap();
+        }
+        out.push_str("</tr>\n");
+    }
+    for row in rows {
+        out.push_str("<tr>");
+        for cell in row {
+            write!(out, "<td>{}</td>", escape_html(cell)).unwrap();
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</table>\n");
+}
+
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; margin-bottom: 2em; }
+th, td { border: 1px solid #ccc; padding: 0.3em 0.8em; text-align: left; }
+img { image-rendering: pixelated; max-width: 100%; border: 1px solid #ccc; }";
+
+/// Render the report as a single HTML file, the minimap is embedded as a
+/// data URI so the file can be shared on its own
+pub fn html(report: &Report) -> String {
+    let mut out = String::new();
+    let title = escape_html(&report.path);
+    write!(
+        out,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{}\n</style>\n</head>\n<body>\n<h1>{}</h1>\n",
+        title, STYLE, title
+    )
+    .unwrap();
+
+    out.push_str("<h2>Summary</h2>\n");
+    let rows: Vec<Vec<String>> = report.summary().into_iter().map(|(k, v)| vec![k, v]).collect();
+    html_table(&mut out, &[], &rows);
+
+    out.push_str("<h2>Companies</h2>\n");
+    html_table(&mut out, &COMPANY_HEADERS, &report.company_rows());
+
+    out.push_str("<h2>NewGRFs</h2>\n");
+    html_table(&mut out, &NEWGRF_HEADERS, &report.newgrf_rows());
+
+    if let Some(png) = minimap::render_png(&report.chunks) {
+        out.push_str("<h2>Minimap</h2>\n");
+        writeln!(out, "<img alt=\"minimap\" src=\"data:image/png;base64,{}\">", base64(&png)).unwrap();
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn markdown_table(out: &mut String, headers: &[&str], rows: &[Vec<String>]) {
+    let escape = |cell: &str| cell.replace('|', "\\|").replace('\n', " ");
+    let cells: Vec<String> = headers.iter().map(|h| escape(h)).collect();
+    writeln!(out, "| {} |", cells.join(" | ")).unwrap();
+    writeln!(out, "|{}", "---|".repeat(headers.len())).unwrap();
+    for row in rows {
+        let cells: Vec<String> = row.iter().map(|c| escape(c)).collect();
+        writeln!(out, "| {} |", cells.join(" | ")).unwrap();
+    }
+    out.push('\n');
+}
+
+/// Render the report as Markdown tables, for forum posts and issues
+pub fn markdown(report: &Report) -> String {
+    let mut out = String::new();
+    writeln!(out, "## {}\n", report.path).unwrap();
+    let rows: Vec<Vec<String>> = report.summary().into_iter().map(|(k, v)| vec![k, v]).collect();
+    markdown_table(&mut out, &["Summary", ""], &rows);
+
+    out.push_str("### Companies\n\n");
+    markdown_table(&mut out, &COMPANY_HEADERS, &report.company_rows());
+
+    out.push_str("### NewGRFs\n\n");
+    markdown_table(&mut out, &NEWGRF_HEADERS, &report.newgrf_rows());
+    out
+}
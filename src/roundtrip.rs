@@ -0,0 +1,78 @@
+use crate::chunk::read_chunks_lenient;
+use crate::reader::Savegame;
+
+/// Read a freshly written savegame back and compare it against the model it
+/// was written from. Chunks the operation did not touch, i.e. that are the
+/// same in `original` and `model`, must also be byte identical to the
+/// original. Returns every difference found.
+pub fn verify(original: &Savegame, model: &Savegame, output: &str) -> Vec<String> {
+    let mut problems = Vec::new();
+    let output = output.to_string();
+    let written = match Savegame::open_lenient(output) {
+        Ok((written, None)) => written,
+        Ok((_, Some(error))) => return vec![format!("written savegame does not decompress: {}", error)],
+        Err(error) => return vec![format!("written savegame has an unreadable header: {}", error)],
+    };
+    if written.version != model.version || written.minor_version != model.minor_version {
+        problems.push(format!(
+            "version is {}.{}, expected {}.{}",
+            written.version, written.minor_version, model.version, model.minor_version
+        ));
+    }
+    if written.compression.tag() != model.compression.tag() {
+        problems.push(format!(
+            "compression is {:?}, expected {:?}",
+            written.compression, model.compression
+        ));
+    }
+
+    let scan = read_chunks_lenient(&written.data);
+    if let Some(error) = scan.error {
+        problems.push(format!("written savegame is corrupt: {}", error));
+    }
+    let expected = model.chunks();
+    let source = original.chunks();
+    if scan.chunks.len() != expected.len() {
+        problems.push(format!(
+            "{} chunks written, expected {}",
+            scan.chunks.len(),
+            expected.len()
+        ));
+    }
+    for (chunk, expected_chunk) in scan.chunks.iter().zip(expected.iter()) {
+        if chunk.id != expected_chunk.id {
+            problems.push(format!("chunk {} written where {} was expected", chunk.id, expected_chunk.id));
+            continue;
+        }
+        if chunk.records() != expected_chunk.records() {
+            problems.push(format!("chunk {} decodes differently", chunk.id));
+        }
+        if written.raw_chunk(chunk) != model.raw_chunk(expected_chunk) {
+            let untouched = source
+                .iter()
+                .find(|c| c.id == chunk.id)
+                .is_some_and(|c| original.raw_chunk(c) == model.raw_chunk(expected_chunk));
+            if untouched {
+                problems.push(format!("untouched chunk {} was changed", chunk.id));
+            } else {
+                problems.push(format!("chunk {} differs from the model", chunk.id));
+            }
+        }
+    }
+    problems
+}
+
+/// Run `verify` and abort with every difference when the written file does
+/// not match
+pub fn verify_or_exit(original: &Savegame, model: &Savegame, output: &str) {
+    let problems = verify(original, model, output);
+    if problems.is_empty() {
+        println!("Round trip verified: {}", output);
+        return;
+    }
+    eprintln!("Round trip verification of {} failed:", output);
+    for problem in problems {
+        eprintln!("  {}", problem);
+    }
+    std::process::exit(2);
+}
@@ -0,0 +1,247 @@
+use crate::chunk::{find, read_chunks_lenient, Chunk};
+use crate::gameinfo;
+use crate::reader::Savegame;
+use crate::summary::companies;
+use serde_json::json;
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+use tiny_http::{Header, Response, Server};
+
+struct Loaded {
+    name: String,
+    modified: SystemTime,
+    savegame: Savegame,
+    chunks: Vec<Chunk>,
+    /// why table chunks do not decode, a request that needs them fails
+    broken: Vec<String>,
+}
+
+fn modified(path: &Path) -> SystemTime {
+    fs::metadata(path).and_then(|m| m.modified()).unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+/// Load a savegame, a broken or half written file is reported instead of
+/// taking the server down
+fn load(path: &Path) -> Option<Loaded> {
+    let name = path.file_name()?.to_string_lossy().to_string();
+    let savegame = match Savegame::open_lenient(path.display().to_string()) {
+        Ok((savegame, None)) => savegame,
+        Ok((_, Some(error))) | Err(error) => {
+            eprintln!("Failed to load {}: {}", path.display(), error);
+            return None;
+        }
+    };
+    let scan = read_chunks_lenient(&savegame.data);
+    if let Some(error) = scan.error {
+        eprintln!("Failed to load {}: {}", path.display(), error);
+        return None;
+    }
+    let broken: Vec<String> = scan
+        .chunks
+        .iter()
+        .filter_map(|chunk| chunk.try_records().err().map(|error| format!("chunk {}: {}", chunk.id, error)))
+        .collect();
+    for error in broken.iter() {
+        eprintln!("{} does not decode, {}", path.display(), error);
+    }
+    Some(Loaded {
+        name,
+        modified: modified(path),
+        savegame,
+        chunks: scan.chunks,
+        broken,
+    })
+}
+
+/// Newest `.sav` file in a directory, like the autosave folder
+pub fn newest_savegame(dir: &str) -> Option<std::path::PathBuf> {
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "sav"))
+        .max_by_key(|path| modified(path))
+}
+
+fn refresh(saves: &mut Vec<Loaded>, dir: &str) {
+    let Some(path) = newest_savegame(dir) else {
+        return;
+    };
+    let name = path.file_name().unwrap().to_string_lossy().to_string();
+    if saves.first().is_some_and(|s| s.name == name && s.modified == modified(&path)) {
+        return;
+    }
+    if let Some(loaded) = load(&path) {
+        println!("Loaded {}", loaded.name);
+        *saves = vec![loaded];
+    }
+}
+
+fn decode_percent(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        out.push(byte);
+                        i += 2;
+                    }
+                    None => out.push(b'%'),
+                }
+            }
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+fn parse_query(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) => (decode_percent(key), decode_percent(value)),
+            None => (decode_percent(pair), String::new()),
+        })
+        .collect()
+}
+
+fn chunk_type_name(chunk: &Chunk) -> String {
+    format!("{:?}", chunk.chunk_type)
+}
+
+fn info(save: &Loaded) -> serde_json::Value {
+    let chunks: Vec<serde_json::Value> = save
+        .chunks
+        .iter()
+        .map(|c| {
+            json!({
+                "id": c.id,
+                "type": chunk_type_name(c),
+                "size": c.size(),
+                "elements": c.elements.len(),
+            })
+        })
+        .collect();
+    json!({
+        "name": save.name,
+        "path": save.savegame.path,
+        "version": save.savegame.version,
+        "compression": format!("{:?}", save.savegame.compression),
+        "size": save.savegame.data.len(),
+        "chunks": chunks,
+    })
+}
+
+fn chunk_json(chunk: &Chunk) -> Result<serde_json::Value, String> {
+    if chunk.header.is_some() {
+        let records: Vec<serde_json::Value> = chunk
+            .try_records()?
+            .iter()
+            .map(|(index, record)| {
+                let mut record = record.to_json();
+                record["index"] = json!(index);
+                record
+            })
+            .collect();
+        return Ok(json!({"id": chunk.id, "type": chunk_type_name(chunk), "records": records}));
+    }
+    if !chunk.chunk_type.is_list() {
+        return Ok(json!({"id": chunk.id, "type": chunk_type_name(chunk), "size": chunk.size()}));
+    }
+    let elements: Vec<serde_json::Value> = chunk
+        .elements
+        .iter()
+        .map(|e| json!({"index": e.index, "size": e.data.len()}))
+        .collect();
+    Ok(json!({
+        "id": chunk.id,
+        "type": chunk_type_name(chunk),
+        "size": chunk.size(),
+        "elements": elements,
+    }))
+}
+
+fn param<'a>(params: &'a [(String, String)], key: &'a str) -> impl Iterator<Item = &'a String> {
+    params.iter().filter(move |(k, _)| k == key).map(|(_, v)| v)
+}
+
+/// `/query?chunk=PLYR&field=money&index=0`, picks fields out of the records
+/// of a table chunk, `field` may be repeated and `index` is optional
+fn query(save: &Loaded, params: &[(String, String)]) -> Option<Result<serde_json::Value, String>> {
+    let chunk = find(&save.chunks, param(params, "chunk").next()?)?;
+    let records = match chunk.try_records() {
+        Ok(records) => records,
+        Err(error) => return Some(Err(error)),
+    };
+    let index: Option<u32> = param(params, "index").next().and_then(|i| i.parse().ok());
+    let fields: Vec<&String> = param(params, "field").collect();
+    let records: Vec<serde_json::Value> = records
+        .iter()
+        .filter(|(i, _)| index.is_none_or(|index| *i == index))
+        .map(|(i, record)| {
+            let mut row = serde_json::Map::new();
+            row.insert("index".to_string(), json!(i));
+            for field in fields.iter() {
+                let value = record.get(field).map(|v| v.to_json());
+                row.insert(field.to_string(), value.unwrap_or(serde_json::Value::Null));
+            }
+            serde_json::Value::Object(row)
+        })
+        .collect();
+    Some(Ok(json!(records)))
+}
+
+/// The response body for `url`, None is a 404 and an error a 500 for
+/// records that do not decode
+fn route(saves: &[Loaded], url: &str) -> Option<Result<serde_json::Value, String>> {
+    let (path, query_string) = url.split_once('?').unwrap_or((url, ""));
+    let params = parse_query(query_string);
+    if path == "/saves" {
+        return Some(Ok(saves.iter().map(|s| json!(s.name)).collect()));
+    }
+    let save = match param(&params, "save").next() {
+        Some(name) => saves.iter().find(|s| &s.name == name)?,
+        None => saves.first()?,
+    };
+    match path {
+        "/info" => Some(Ok(info(save))),
+        // these read several chunks, any of them may be broken
+        "/game-info" | "/companies" if !save.broken.is_empty() => Some(Err(save.broken.join(", "))),
+        "/game-info" => Some(Ok(gameinfo::json(&save.name, &save.chunks))),
+        "/companies" => Some(Ok(companies(&save.chunks).iter().map(|c| c.to_json()).collect())),
+        "/query" => query(save, &params),
+        _ => {
+            let id = path.strip_prefix("/chunks/")?;
+            find(&save.chunks, id).map(chunk_json)
+        }
+    }
+}
+
+/// Serve the given savegames as JSON, with `watch` set the newest savegame
+/// in that directory is (re)loaded whenever a request comes in. Only `bind`
+/// can reach the server, the default keeps it on this machine.
+pub fn serve(bind: &str, port: u16, paths: &[String], watch: Option<&str>) {
+    let mut saves: Vec<Loaded> = paths.iter().filter_map(|p| load(Path::new(p))).collect();
+    let server = Server::http((bind, port)).unwrap();
+    println!("Listening on http://{}:{}", bind, port);
+    let content_type = Header::from_bytes("Content-Type", "application/json").unwrap();
+    for request in server.incoming_requests() {
+        if let Some(dir) = watch {
+            refresh(&mut saves, dir);
+        }
+        let response = match route(&saves, request.url()) {
+            Some(Ok(body)) => Response::from_string(body.to_string()),
+            Some(Err(error)) => Response::from_string(json!({"error": error}).to_string()).with_status_code(500),
+            None => Response::from_string(json!({"error": "not found"}).to_string())
+                .with_status_code(404),
+        };
+        let _ = request.respond(response.with_header(content_type.clone()));
+    }
+}
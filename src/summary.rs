@@ -0,0 +1,182 @@
+use crate::chunk::{find, Chunk};
+use crate::table::Value;
+use std::collections::BTreeMap;
+
+#[derive(Debug)]
+pub struct Company {
+    pub id: u32,
+    pub name: String,
+    pub president: String,
+    pub money: i64,
+    pub loan: i64,
+    /// cargo delivered in the current economy period, all cargo types
+    pub delivered: i64,
+}
+
+pub const VEHICLE_TYPES: [&str; 4] = ["train", "road", "ship", "aircraft"];
+
+fn get_i64(record: &Value, key: &str) -> i64 {
+    record.get(key).and_then(Value::as_i64).unwrap_or(0)
+}
+
+fn find_i64(record: &Value, key: &str) -> i64 {
+    record.find(key).and_then(Value::as_i64).unwrap_or(0)
+}
+
+fn get_string(record: &Value, key: &str) -> String {
+    record.get(key).and_then(Value::as_str).unwrap_or("").to_string()
+}
+
+/// Companies from the PLYR chunk, custom names only, generated names are
+/// string ids that need the language files to resolve
+pub fn companies(chunks: &[Chunk]) -> Vec<Company> {
+    let Some(chunk) = find(chunks, "PLYR") else {
+        return Vec::new();
+    };
+    chunk
+        .records()
+        .iter()
+        .map(|(id, record)| Company {
+            id: *id,
+            name: get_string(record, "name"),
+            president: get_string(record, "president_name"),
+            money: get_i64(record, "money"),
+            loan: get_i64(record, "current_loan"),
+            delivered: delivered_cargo(record),
+        })
+        .collect()
+}
+
+fn delivered_cargo(record: &Value) -> i64 {
+    let delivered = record
+        .find("cur_economy")
+        .and_then(|economy| economy.find("delivered_cargo"));
+    match delivered {
+        Some(Value::List(values)) => values.iter().filter_map(Value::as_i64).sum(),
+        Some(value) => value.as_i64().unwrap_or(0),
+        None => 0,
+    }
+}
+
+/// Whether a vehicle is something a player would count, articulated parts,
+/// wagons, aircraft shadows and rotors are left out
+fn is_primary_vehicle(vehicle_type: i64, subtype: i64) -> bool {
+    match vehicle_type {
+        0 | 1 => subtype & 1 != 0,
+        2 => true,
+        3 => subtype <= 2,
+        _ => false,
+    }
+}
+
+/// Number of primary vehicles per (owner, vehicle type) from the VEHS chunk
+pub fn vehicle_counts(chunks: &[Chunk]) -> BTreeMap<(i64, &'static str), usize> {
+    let mut counts = BTreeMap::new();
+    let Some(chunk) = find(chunks, "VEHS") else {
+        return counts;
+    };
+    for (_, record) in chunk.records() {
+        let vehicle_type = find_i64(&record, "type");
+        if !is_primary_vehicle(vehicle_type, find_i64(&record, "subtype")) {
+            continue;
+        }
+        let key = (find_i64(&record, "owner"), VEHICLE_TYPES[vehicle_type as usize]);
+        *counts.entry(key).or_insert(0) += 1;
+    }
+    counts
+}
+
+impl Company {
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "id": self.id,
+            "name": self.name,
+            "president": self.president,
+            "money": self.money,
+            "loan": self.loan,
+            "delivered": self.delivered,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct NewGrf {
+    pub grfid: u32,
+    pub filename: String,
+    pub md5sum: String,
+    pub version: i64,
+}
+
+impl NewGrf {
+    /// grfid in the byte order OpenTTD shows it, e.g. 4D470101
+    pub fn grfid_hex(&self) -> String {
+        format!("{:08X}", self.grfid.swap_bytes())
+    }
+}
+
+/// NewGRFs from the NGRF chunk
+pub fn newgrfs(chunks: &[Chunk]) -> Vec<NewGrf> {
+    let Some(chunk) = find(chunks, "NGRF") else {
+        return Vec::new();
+    };
+    chunk
+        .records()
+        .iter()
+        .map(|(_, record)| {
+            let md5sum = match record.get("md5sum") {
+                Some(Value::List(bytes)) => bytes
+                    .iter()
+                    .map(|b| format!("{:02x}", b.as_i64().unwrap_or(0)))
+                    .collect(),
+                _ => String::new(),
+            };
+            NewGrf {
+                grfid: get_i64(record, "grfid") as u32,
+                filename: get_string(record, "filename"),
+                md5sum,
+                version: get_i64(record, "version"),
+            }
+        })
+        .collect()
+}
+
+/// Turn an OpenTTD date (days since 1-1-0) into (year, month, day)
+pub fn ymd(date: i64) -> (i64, u32, u32) {
+    // days since 1970-01-01, then the usual civil calendar conversion
+    let days = date - 719528 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+pub fn format_date((year, month, day): (i64, u32, u32)) -> String {
+    format!("{}-{:02}-{:02}", year, month, day)
+}
+
+/// Current game date from the DATE chunk, in days since 1-1-0
+pub fn date_number(chunks: &[Chunk]) -> Option<i64> {
+    let (_, record) = find(chunks, "DATE")?.records().into_iter().next()?;
+    record.get("date").and_then(Value::as_i64)
+}
+
+/// Current game date from the DATE chunk, as (year, month, day)
+pub fn date(chunks: &[Chunk]) -> Option<(i64, u32, u32)> {
+    date_number(chunks).map(ymd)
+}
+
+/// Map dimensions from the MAPS chunk
+pub fn map_size(chunks: &[Chunk]) -> Option<(u32, u32)> {
+    let (_, record) = find(chunks, "MAPS")?.records().into_iter().next()?;
+    Some((get_i64(&record, "dim_x") as u32, get_i64(&record, "dim_y") as u32))
+}
+
+/// Number of elements in a list chunk, 0 when the chunk is missing
+pub fn count(chunks: &[Chunk], id: &str) -> usize {
+    find(chunks, id).map_or(0, |chunk| chunk.elements.len())
+}
@@ -0,0 +1,316 @@
+use crate::chunk::{need, read_bytes, read_gamma, read_u8};
+use crate::reader::{DataReader, Reader};
+
+pub const FILE_END: u8 = 0;
+pub const FILE_I8: u8 = 1;
+pub const FILE_U8: u8 = 2;
+pub const FILE_I16: u8 = 3;
+pub const FILE_U16: u8 = 4;
+pub const FILE_I32: u8 = 5;
+pub const FILE_U32: u8 = 6;
+pub const FILE_I64: u8 = 7;
+pub const FILE_U64: u8 = 8;
+pub const FILE_STRINGID: u8 = 9;
+pub const FILE_STRING: u8 = 10;
+pub const FILE_STRUCT: u8 = 11;
+pub const FILE_TYPE_MASK: u8 = 0x0F;
+pub const FILE_HAS_LENGTH_FIELD: u8 = 0x10;
+
+/// A field description from the header of a table chunk
+#[derive(Debug, Clone)]
+pub struct Field {
+    pub key: String,
+    pub field_type: u8,
+    /// description of the nested fields, only used by struct fields
+    pub fields: Vec<Field>,
+}
+
+impl Field {
+    pub fn new(key: &str, field_type: u8) -> Self {
+        Field {
+            key: key.to_string(),
+            field_type,
+            fields: Vec::new(),
+        }
+    }
+
+    /// a list of structs, the way SL_STRUCTLIST fields are saved
+    pub fn structs(key: &str, fields: Vec<Field>) -> Self {
+        Field {
+            key: key.to_string(),
+            field_type: FILE_STRUCT | FILE_HAS_LENGTH_FIELD,
+            fields,
+        }
+    }
+
+    pub fn base_type(&self) -> u8 {
+        self.field_type & FILE_TYPE_MASK
+    }
+
+    pub fn has_length(&self) -> bool {
+        self.field_type & FILE_HAS_LENGTH_FIELD != 0
+    }
+
+    /// whether the field holds exactly one number
+    #[cfg(feature = "parquet")]
+    pub fn is_scalar(&self) -> bool {
+        !self.has_length() && (FILE_I8..=FILE_STRINGID).contains(&self.base_type())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i64),
+    UInt(u64),
+    Str(String),
+    List(Vec<Value>),
+    Struct(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Int(value) => Some(*value),
+            Value::UInt(value) => Some(*value as i64),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Str(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            Value::Int(value) => serde_json::json!(value),
+            Value::UInt(value) => serde_json::json!(value),
+            Value::Str(value) => serde_json::json!(value),
+            Value::List(values) => values.iter().map(Value::to_json).collect(),
+            Value::Struct(fields) => fields
+                .iter()
+                .map(|(key, value)| (key.clone(), value.to_json()))
+                .collect::<serde_json::Map<_, _>>()
+                .into(),
+        }
+    }
+
+    /// look up a field of a struct value
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Struct(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// look up a field anywhere below this value, table savegames wrap a
+    /// lot of fields in nested structs
+    pub fn find(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Struct(fields) => fields
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v)
+                .or_else(|| fields.iter().find_map(|(_, v)| v.find(key))),
+            Value::List(values) => values.iter().find_map(|v| v.find(key)),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Value::Int(value) => write!(f, "{}", value),
+            Value::UInt(value) => write!(f, "{}", value),
+            Value::Str(value) => write!(f, "{}", value),
+            Value::List(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            }
+            Value::Struct(fields) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", key, value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn read_fields(reader: &mut impl Reader) -> Result<Vec<Field>, String> {
+    let mut fields = Vec::new();
+    loop {
+        let field_type = read_u8(reader)?;
+        if field_type == FILE_END {
+            break;
+        }
+        let len = read_gamma(reader)? as usize;
+        let key = String::from_utf8_lossy(&read_bytes(reader, len)?).to_string();
+        fields.push(Field {
+            key,
+            field_type,
+            fields: Vec::new(),
+        });
+    }
+    // nested headers follow in the same order as the struct fields
+    for field in fields.iter_mut() {
+        if field.base_type() == FILE_STRUCT {
+            field.fields = read_fields(reader)?;
+        }
+    }
+    Ok(fields)
+}
+
+/// Parse the header of a table chunk
+pub fn read_header(header: &[u8]) -> Result<Vec<Field>, String> {
+    let mut reader = DataReader::new(header);
+    read_fields(&mut reader)
+}
+
+fn read_single(reader: &mut impl Reader, field: &Field) -> Result<Value, String> {
+    let size = match field.base_type() {
+        FILE_I8 | FILE_U8 => 1,
+        FILE_I16 | FILE_U16 | FILE_STRINGID => 2,
+        FILE_I32 | FILE_U32 => 4,
+        FILE_I64 | FILE_U64 => 8,
+        FILE_STRUCT => return Ok(Value::Struct(read_values(reader, &field.fields)?)),
+        _ => return Err(format!("unknown field type {} for {}", field.field_type, field.key)),
+    };
+    need(reader, size)?;
+    Ok(match field.base_type() {
+        FILE_I8 => Value::Int(reader.read_i8() as i64),
+        FILE_U8 => Value::UInt(reader.read_u8() as u64),
+        FILE_I16 => Value::Int(reader.read_i16() as i64),
+        FILE_U16 | FILE_STRINGID => Value::UInt(reader.read_u16() as u64),
+        FILE_I32 => Value::Int(reader.read_i32() as i64),
+        FILE_U32 => Value::UInt(reader.read_u32() as u64),
+        FILE_I64 => Value::Int(reader.read_i64()),
+        _ => Value::UInt(reader.read_u64()),
+    })
+}
+
+fn read_value(reader: &mut impl Reader, field: &Field) -> Result<Value, String> {
+    if field.base_type() == FILE_STRING {
+        let len = read_gamma(reader)? as usize;
+        return Ok(Value::Str(String::from_utf8_lossy(&read_bytes(reader, len)?).to_string()));
+    }
+    if !field.has_length() {
+        return read_single(reader, field);
+    }
+    let len = read_gamma(reader)?;
+    let values = (0..len).map(|_| read_single(reader, field)).collect::<Result<_, _>>()?;
+    Ok(Value::List(values))
+}
+
+fn read_values(reader: &mut impl Reader, fields: &[Field]) -> Result<Vec<(String, Value)>, String> {
+    fields
+        .iter()
+        .map(|field| Ok((field.key.clone(), read_value(reader, field)?)))
+        .collect()
+}
+
+/// Decode one element of a table chunk using its header, data that runs
+/// out early or an unknown field type is an error
+pub fn read_record(fields: &[Field], data: &[u8]) -> Result<Value, String> {
+    let mut reader = DataReader::new(data);
+    Ok(Value::Struct(read_values(&mut reader, fields)?))
+}
+
+pub fn write_gamma(out: &mut Vec<u8>, value: u32) {
+    if value < 0x80 {
+        out.push(value as u8);
+    } else if value < 0x4000 {
+        out.extend_from_slice(&((value | 0x8000) as u16).to_be_bytes());
+    } else if value < 0x20_0000 {
+        out.extend_from_slice(&(value | 0xC0_0000).to_be_bytes()[1..]);
+    } else if value < 0x1000_0000 {
+        out.extend_from_slice(&(value | 0xE000_0000).to_be_bytes());
+    } else {
+        out.push(0xF0);
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+}
+
+fn write_fields(out: &mut Vec<u8>, fields: &[Field]) {
+    for field in fields {
+        out.push(field.field_type);
+        write_gamma(out, field.key.len() as u32);
+        out.extend_from_slice(field.key.as_bytes());
+    }
+    out.push(FILE_END);
+    for field in fields {
+        if field.base_type() == FILE_STRUCT {
+            write_fields(out, &field.fields);
+        }
+    }
+}
+
+/// Build the header of a table chunk, the reverse of `read_header`
+pub fn write_header(fields: &[Field]) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_fields(&mut out, fields);
+    out
+}
+
+fn write_single(out: &mut Vec<u8>, field: &Field, value: &Value) {
+    let number = match value {
+        Value::Int(value) => *value,
+        Value::UInt(value) => *value as i64,
+        Value::Struct(fields) => {
+            for nested in field.fields.iter() {
+                let value = fields.iter().find(|(k, _)| *k == nested.key).map(|(_, v)| v);
+                write_value(out, nested, value.unwrap_or(&Value::UInt(0)));
+            }
+            return;
+        }
+        _ => panic!("Cannot write {} as {}", value, field.key),
+    };
+    match field.base_type() {
+        FILE_I8 | FILE_U8 => out.push(number as u8),
+        FILE_I16 | FILE_U16 | FILE_STRINGID => out.extend_from_slice(&(number as u16).to_be_bytes()),
+        FILE_I32 | FILE_U32 => out.extend_from_slice(&(number as u32).to_be_bytes()),
+        FILE_I64 | FILE_U64 => out.extend_from_slice(&number.to_be_bytes()),
+        _ => panic!("Unknown field type {} for {}", field.field_type, field.key),
+    }
+}
+
+fn write_value(out: &mut Vec<u8>, field: &Field, value: &Value) {
+    if field.base_type() == FILE_STRING {
+        let text = value.as_str().unwrap_or("");
+        write_gamma(out, text.len() as u32);
+        out.extend_from_slice(text.as_bytes());
+        return;
+    }
+    if !field.has_length() {
+        return write_single(out, field, value);
+    }
+    let values = match value {
+        Value::List(values) => &values[..],
+        _ => &[],
+    };
+    write_gamma(out, values.len() as u32);
+    for value in values {
+        write_single(out, field, value);
+    }
+}
+
+/// Encode one element of a table chunk, the reverse of `read_record`,
+/// fields missing from `record` are written as 0
+pub fn write_record(fields: &[Field], record: &Value) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_single(&mut out, &Field::structs("", fields.to_vec()), record);
+    out
+}
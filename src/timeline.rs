@@ -0,0 +1,160 @@
+use crate::chunk::{find, read_chunks_lenient};
+use crate::reader::Savegame;
+use crate::summary::{companies, date, format_date, vehicle_counts, Company};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// State of the game in one savegame of the series
+pub struct Snapshot {
+    pub file: String,
+    pub date: (i64, u32, u32),
+    pub companies: Vec<Company>,
+    /// vehicles per company
+    pub vehicles: BTreeMap<i64, usize>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Interval {
+    All,
+    Monthly,
+    Yearly,
+}
+
+impl Interval {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "all" => Some(Interval::All),
+            "monthly" => Some(Interval::Monthly),
+            "yearly" => Some(Interval::Yearly),
+            _ => None,
+        }
+    }
+
+    fn bucket(&self, (year, month, day): (i64, u32, u32)) -> (i64, u32, u32) {
+        match self {
+            Interval::All => (year, month, day),
+            Interval::Monthly => (year, month, 0),
+            Interval::Yearly => (year, 0, 0),
+        }
+    }
+}
+
+/// The snapshot of one savegame, a file that does not load or has no game
+/// date is an error
+fn snapshot(path: String) -> Result<Snapshot, String> {
+    let (savegame, error) = Savegame::open_lenient(path.clone())?;
+    if let Some(error) = error {
+        return Err(error);
+    }
+    let scan = read_chunks_lenient(&savegame.data);
+    if let Some(error) = scan.error {
+        return Err(error);
+    }
+    let chunks = scan.chunks;
+    // the chunks decoded below
+    for id in ["DATE", "PLYR", "VEHS"] {
+        if let Some(chunk) = find(&chunks, id) {
+            chunk.try_records().map_err(|error| format!("chunk {}: {}", id, error))?;
+        }
+    }
+    let mut vehicles = BTreeMap::new();
+    for ((owner, _), count) in vehicle_counts(&chunks) {
+        *vehicles.entry(owner).or_insert(0) += count;
+    }
+    Ok(Snapshot {
+        file: path,
+        date: date(&chunks).ok_or("no game date")?,
+        companies: companies(&chunks),
+        vehicles,
+    })
+}
+
+/// Read every savegame in `dir` and order them by game date, keeping the
+/// latest savegame of each interval
+pub fn collect(dir: &str, interval: Interval) -> Vec<Snapshot> {
+    let mut paths: Vec<String> = std::fs::read_dir(dir)
+        .unwrap()
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "sav"))
+        .map(|path| path.display().to_string())
+        .collect();
+    paths.sort();
+
+    let mut buckets: BTreeMap<(i64, u32, u32), Snapshot> = BTreeMap::new();
+    for path in paths {
+        let snapshot = match snapshot(path.clone()) {
+            Ok(snapshot) => snapshot,
+            Err(error) => {
+                eprintln!("Skipping {}: {}", path, error);
+                continue;
+            }
+        };
+        let bucket = interval.bucket(snapshot.date);
+        if buckets.get(&bucket).is_none_or(|existing| existing.date <= snapshot.date) {
+            buckets.insert(bucket, snapshot);
+        }
+    }
+    buckets.into_values().collect()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// One row per date and value, easy to pivot in pandas or a spreadsheet
+pub fn csv(snapshots: &[Snapshot]) -> String {
+    let mut out = String::from("date,file,entity,id,name,metric,value\n");
+    for snapshot in snapshots {
+        let prefix = format!("{},{}", format_date(snapshot.date), csv_field(&snapshot.file));
+        for company in snapshot.companies.iter() {
+            let vehicles = snapshot.vehicles.get(&(company.id as i64)).copied().unwrap_or(0);
+            let metrics = [
+                ("money", company.money),
+                ("loan", company.loan),
+                ("delivered", company.delivered),
+                ("vehicles", vehicles as i64),
+            ];
+            for (metric, value) in metrics {
+                writeln!(
+                    out,
+                    "{},company,{},{},{},{}",
+                    prefix,
+                    company.id,
+                    csv_field(&company.name),
+                    metric,
+                    value
+                )
+                .unwrap();
+            }
+        }
+    }
+    out
+}
+
+pub fn json(snapshots: &[Snapshot]) -> serde_json::Value {
+    snapshots
+        .iter()
+        .map(|snapshot| {
+            let companies: Vec<serde_json::Value> = snapshot
+                .companies
+                .iter()
+                .map(|company| {
+                    let mut value = company.to_json();
+                    let vehicles = snapshot.vehicles.get(&(company.id as i64)).copied().unwrap_or(0);
+                    value["vehicles"] = json!(vehicles);
+                    value
+                })
+                .collect();
+            json!({
+                "date": format_date(snapshot.date),
+                "file": snapshot.file,
+                "companies": companies,
+            })
+        })
+        .collect()
+}
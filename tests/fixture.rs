@@ -0,0 +1,117 @@
+use savegame_reader::chunk::{chunk_index, read_chunks, write_chunks};
+use savegame_reader::fixture::{default_chunks, standard, STANDARD_CHUNKS};
+use savegame_reader::reader::{CompressionType, Savegame};
+use savegame_reader::registry;
+use savegame_reader::summary::{companies, date, map_size, newgrfs, vehicle_counts};
+use savegame_reader::table::{read_header, read_record, write_header, write_record};
+
+fn savegame(compression: CompressionType) -> Savegame {
+    standard(300, compression, &STANDARD_CHUNKS).build("fixture.sav")
+}
+
+#[test]
+fn chunks_round_trip() {
+    let savegame = savegame(CompressionType::None);
+    let chunks = savegame.chunks();
+    assert_eq!(chunks.iter().map(|c| c.id.as_str()).collect::<Vec<_>>(), STANDARD_CHUNKS);
+    let written = write_chunks(&chunks);
+    assert_eq!(written, savegame.data);
+    let reread = read_chunks(&written);
+    for (chunk, original) in reread.iter().zip(chunks.iter()) {
+        assert_eq!(chunk.records(), original.records());
+        assert_eq!(chunk.data, original.data);
+    }
+}
+
+#[test]
+fn records_round_trip() {
+    for chunk in savegame(CompressionType::None).chunks() {
+        let Some(header) = &chunk.header else {
+            continue;
+        };
+        let fields = read_header(header).unwrap();
+        assert_eq!(&write_header(&fields), header, "header of {}", chunk.id);
+        for element in chunk.elements.iter() {
+            let record = read_record(&fields, &element.data).unwrap();
+            assert_eq!(write_record(&fields, &record), element.data, "record of {}", chunk.id);
+        }
+    }
+}
+
+#[test]
+fn summary() {
+    let chunks = savegame(CompressionType::None).chunks();
+    assert_eq!(date(&chunks), Some((1950, 1, 1)));
+    assert_eq!(map_size(&chunks), Some((64, 64)));
+
+    let companies = companies(&chunks);
+    assert_eq!(companies.len(), 2);
+    assert_eq!(companies[0].name, "Fixture Transport");
+    assert_eq!(companies[0].money, 100000);
+    assert_eq!(companies[0].delivered, 150);
+    assert_eq!(companies[1].id, 1);
+    assert_eq!(companies[1].president, "");
+
+    // wagons and aircraft shadows are not counted
+    let counts: Vec<_> = vehicle_counts(&chunks).into_iter().collect();
+    assert_eq!(counts, vec![((0, "aircraft"), 1), ((0, "train"), 1), ((1, "road"), 1)]);
+
+    let grfs = newgrfs(&chunks);
+    assert_eq!(grfs.len(), 1);
+    assert_eq!(grfs[0].grfid_hex(), "4D470101");
+    assert_eq!(grfs[0].md5sum, "000102030405060708090a0b0c0d0e0f");
+}
+
+#[test]
+fn encode_round_trip() {
+    for compression in [CompressionType::None, CompressionType::Zlib, CompressionType::Lzma] {
+        let savegame = savegame(compression.clone());
+        let bytes = savegame.encode();
+        assert_eq!(&bytes[..4], compression.tag());
+        let (decoded, error) = Savegame::from_bytes("fixture.sav".to_string(), &bytes).unwrap();
+        assert_eq!(error, None);
+        assert_eq!(decoded.version, 300);
+        assert_eq!(decoded.compression.tag(), compression.tag());
+        assert_eq!(decoded.data, savegame.data);
+    }
+}
+
+#[test]
+fn chosen_chunks_only() {
+    let savegame = standard(200, CompressionType::Zlib, &["MAPS", "DATE"]).build("fixture.sav");
+    let ids: Vec<String> = savegame.chunks().into_iter().map(|c| c.id).collect();
+    assert_eq!(ids, vec!["MAPS", "DATE"]);
+    assert_eq!(savegame.version, 200);
+}
+
+#[test]
+fn truncated_header_is_an_error() {
+    let bytes = savegame(CompressionType::None).encode();
+    assert!(Savegame::from_bytes("fixture.sav".to_string(), &bytes[..6]).is_err());
+}
+
+#[test]
+fn index_matches_chunks() {
+    let savegame = savegame(CompressionType::None);
+    let index = savegame.chunk_index().unwrap();
+    let chunks = savegame.chunks();
+    assert_eq!(index.len(), chunks.len());
+    for (entry, chunk) in index.iter().zip(chunks.iter()) {
+        assert_eq!((entry.id.as_str(), entry.offset, entry.len), (chunk.id.as_str(), chunk.offset, chunk.len));
+        assert_eq!(entry.kind, chunk.chunk_type);
+        assert_eq!(&savegame.data[entry.offset..entry.offset + 4], entry.id.as_bytes());
+    }
+    // the end marker follows the last chunk
+    let last = index.last().unwrap();
+    assert_eq!(last.offset + last.len + 4, savegame.data.len());
+    assert!(chunk_index(&savegame.data[..savegame.data.len() - 10]).is_err());
+}
+
+#[test]
+fn default_chunks_pass_the_registry() {
+    let chunks = standard(300, CompressionType::None, &default_chunks(300)).build("fixture.sav").chunks();
+    let check = registry::check(300, &chunks);
+    assert!(check.is_clean(), "{:?}", check);
+    // stubs come after the chunks with contents
+    assert_eq!(chunks[..STANDARD_CHUNKS.len()].iter().map(|c| c.id.as_str()).collect::<Vec<_>>(), STANDARD_CHUNKS);
+}
@@ -0,0 +1,53 @@
+use savegame_reader::fixture::{record, standard, Fixture, STANDARD_CHUNKS};
+use savegame_reader::gameinfo::json;
+use savegame_reader::reader::CompressionType;
+use savegame_reader::table::{Field, Value, FILE_HAS_LENGTH_FIELD, FILE_U8};
+
+#[test]
+fn standard_fixture() {
+    let chunks = standard(300, CompressionType::None, &STANDARD_CHUNKS).build("fixture.sav").chunks();
+    let info = json("fixture.sav", &chunks);
+    assert_eq!(info["name"], "fixture.sav");
+    assert_eq!(info["openttd_version"], "14.1");
+    assert_eq!(info["map_type"], 1);
+    assert_eq!(info["map_width"], 64);
+    assert_eq!(info["map_height"], 64);
+    assert_eq!(info["game_date"], 712223);
+    assert_eq!(info["companies_on"], 2);
+    assert_eq!(info["newgrfs"][0]["grfid"], 0x0101474D);
+    assert_eq!(info["newgrfs"][0]["md5sum"], "000102030405060708090a0b0c0d0e0f");
+}
+
+/// before SLV_STRING_GAMELOG the revision is a zero padded byte array
+#[test]
+fn revision_as_bytes() {
+    let mut revision: Vec<Value> = b"13.4".iter().map(|b| Value::UInt(*b as u64)).collect();
+    revision.resize(15, Value::UInt(0));
+    let chunks = Fixture::new(300, CompressionType::None)
+        .table(
+            "GLOG",
+            false,
+            vec![Field::structs(
+                "action",
+                vec![Field::structs(
+                    "revision",
+                    vec![Field::new("revision.text", FILE_U8 | FILE_HAS_LENGTH_FIELD)],
+                )],
+            )],
+            vec![(
+                0,
+                record(&[(
+                    "action",
+                    Value::List(vec![record(&[(
+                        "revision",
+                        Value::List(vec![record(&[("revision.text", Value::List(revision))])]),
+                    )])]),
+                )]),
+            )],
+        )
+        .build("old.sav")
+        .chunks();
+    let info = json("old.sav", &chunks);
+    assert_eq!(info["openttd_version"], "13.4");
+    assert_eq!(info["map_type"], 0);
+}
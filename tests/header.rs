@@ -0,0 +1,44 @@
+use savegame_reader::fixture::{standard, STANDARD_CHUNKS};
+use savegame_reader::header::{build_header, detect_compression, patch, read_header, HEADER_SIZE};
+use savegame_reader::reader::CompressionType;
+
+/// A fixture written to the temp directory, `name` keeps the tests apart
+fn write(name: &str, compression: CompressionType) -> String {
+    let path = std::env::temp_dir().join(name).display().to_string();
+    standard(300, compression, &STANDARD_CHUNKS).build(&path).write(path.clone());
+    path
+}
+
+#[test]
+fn patch_in_place() {
+    let path = write("header-in-place.sav", CompressionType::Zlib);
+    let before = std::fs::read(&path).unwrap();
+    let header = build_header(&CompressionType::Zlib, 301, 0);
+    // a different spelling of the same file is still patched in place
+    let same = std::env::temp_dir().join(".").join("header-in-place.sav").display().to_string();
+    patch(&path, &same, &header);
+    let after = std::fs::read(&path).unwrap();
+    assert_eq!(after.len(), before.len());
+    assert_eq!(after[..HEADER_SIZE], header);
+    assert_eq!(after[HEADER_SIZE..], before[HEADER_SIZE..]);
+    assert_eq!(read_header(&path).unwrap(), header);
+}
+
+#[test]
+fn compression_is_detected() {
+    for (name, compression) in [
+        ("header-none.sav", CompressionType::None),
+        ("header-zlib.sav", CompressionType::Zlib),
+        ("header-xz.sav", CompressionType::Lzma),
+    ] {
+        let path = write(name, compression.clone());
+        assert_eq!(detect_compression(&path).map(|c| c.tag().to_vec()), Some(compression.tag().to_vec()));
+    }
+}
+
+#[test]
+fn short_file_is_an_error() {
+    let path = std::env::temp_dir().join("header-short.sav").display().to_string();
+    std::fs::write(&path, b"OTTZ\x01").unwrap();
+    assert_eq!(read_header(&path).unwrap_err(), "Header truncated, the file has only 5 bytes");
+}
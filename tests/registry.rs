@@ -0,0 +1,23 @@
+use savegame_reader::fixture::Fixture;
+use savegame_reader::reader::CompressionType;
+use savegame_reader::registry::check;
+
+#[test]
+fn version_range() {
+    let chunks = Fixture::new(300, CompressionType::None)
+        .riff("WRGN", Vec::new())
+        .riff("STNS", Vec::new())
+        .riff("LEAE", Vec::new())
+        .riff("ABCD", Vec::new())
+        .build("fixture.sav")
+        .chunks();
+    let result = check(300, &chunks);
+    assert_eq!(result.unknown, vec!["ABCD"]);
+    // water regions are newer than 300, old stations were replaced long before
+    assert_eq!(result.unexpected, vec!["WRGN", "STNS"]);
+    assert!(result.missing.contains(&"DATE"));
+    assert!(!result.is_clean());
+
+    let result = check(324, &chunks);
+    assert_eq!(result.unexpected, vec!["STNS"]);
+}